@@ -9,11 +9,13 @@ use std::time::Duration;
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use dozr::conditions::{
-    DurationWait, ExponentialWait, GammaWait, LogNormalWait, NormalWait,
+    DurationWait, ExponentialWait, GammaWait, LogNormalParams, LogNormalWait, NormalWait,
     ParetoWait, TriangularWait, UniformWait, WaitCondition,
 };
+use dozr::context::WaitContext;
 
 fn bench_duration_calculation(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = DurationWait {
         duration: Duration::from_secs(1),
         verbose: None,
@@ -21,11 +23,12 @@ fn bench_duration_calculation(c: &mut Criterion) {
     };
 
     c.bench_function("duration_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_duration_with_jitter(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = DurationWait {
         duration: Duration::from_secs(1),
         verbose: None,
@@ -33,11 +36,12 @@ fn bench_duration_with_jitter(c: &mut Criterion) {
     };
 
     c.bench_function("duration_with_jitter_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_normal_distribution(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = NormalWait {
         mean: Duration::from_secs(1),
         std_dev: 0.1,
@@ -46,11 +50,12 @@ fn bench_normal_distribution(c: &mut Criterion) {
     };
 
     c.bench_function("normal_distribution_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_exponential_distribution(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = ExponentialWait {
         lambda: 1.0,
         verbose: None,
@@ -58,11 +63,12 @@ fn bench_exponential_distribution(c: &mut Criterion) {
     };
 
     c.bench_function("exponential_distribution_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_uniform_distribution(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = UniformWait {
         min: Duration::from_millis(500),
         max: Duration::from_secs(2),
@@ -71,24 +77,25 @@ fn bench_uniform_distribution(c: &mut Criterion) {
     };
 
     c.bench_function("uniform_distribution_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_lognormal_distribution(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = LogNormalWait {
-        mean: Duration::from_secs(1),
-        std_dev: 0.5,
+        params: LogNormalParams::Log { mu: 0.0, sigma: 0.5 },
         verbose: None,
         jitter: None,
     };
 
     c.bench_function("lognormal_distribution_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_pareto_distribution(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = ParetoWait {
         scale: 1.0,
         shape: 2.0,
@@ -97,11 +104,12 @@ fn bench_pareto_distribution(c: &mut Criterion) {
     };
 
     c.bench_function("pareto_distribution_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_triangular_distribution(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = TriangularWait {
         min: 0.5,
         max: 2.0,
@@ -111,11 +119,12 @@ fn bench_triangular_distribution(c: &mut Criterion) {
     };
 
     c.bench_function("triangular_distribution_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
 fn bench_gamma_distribution(c: &mut Criterion) {
+    let ctx = WaitContext::system();
     let wait = GammaWait {
         shape: 2.0,
         scale: 0.5,
@@ -124,7 +133,7 @@ fn bench_gamma_distribution(c: &mut Criterion) {
     };
 
     c.bench_function("gamma_distribution_calculate", |b| {
-        b.iter(|| black_box(wait.calculate_wait_duration()))
+        b.iter(|| black_box(wait.calculate_wait_duration(&ctx)))
     });
 }
 
@@ -0,0 +1,50 @@
+//! Example demonstrating non-blocking waits on a tokio runtime.
+//!
+//! Run with: `cargo run --example async_wait --features tokio`
+
+use std::time::Duration;
+
+use dozr::conditions::{DurationWait, ExponentialWait, WaitCondition};
+use dozr::context::WaitContext;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let ctx = WaitContext::system();
+
+    println!("Starting a 1-second wait without blocking the runtime...");
+    let wait = DurationWait {
+        duration: Duration::from_secs(1),
+        verbose: None,
+        jitter: None,
+    };
+    wait.wait_async(&ctx).await?;
+    println!("Wait complete!");
+
+    // Dropping the future cancels the wait, e.g. when a timeout fires first.
+    println!("\nStarting a 60-second wait, cancelled after 500ms...");
+    let long_wait = DurationWait {
+        duration: Duration::from_secs(60),
+        verbose: None,
+        jitter: None,
+    };
+    if tokio::time::timeout(Duration::from_millis(500), long_wait.wait_async(&ctx))
+        .await
+        .is_err()
+    {
+        println!("Wait cancelled!");
+    }
+
+    // Pace a few "requests" with exponentially distributed gaps (mean 200ms).
+    println!("\nPacing requests with exponential gaps...");
+    let pacing = ExponentialWait {
+        lambda: 5.0,
+        verbose: None,
+        jitter: None,
+    };
+    for request in 1..=3 {
+        pacing.wait_async(&ctx).await?;
+        println!("  Request {request} sent");
+    }
+
+    Ok(())
+}
@@ -5,8 +5,11 @@
 use std::time::Duration;
 
 use dozr::conditions::{DurationWait, WaitCondition};
+use dozr::context::WaitContext;
 
 fn main() -> anyhow::Result<()> {
+    let ctx = WaitContext::system();
+
     println!("Starting a 2-second wait...");
 
     let wait = DurationWait {
@@ -15,7 +18,7 @@ fn main() -> anyhow::Result<()> {
         jitter: None,
     };
 
-    wait.wait()?;
+    wait.wait(&ctx)?;
 
     println!("Wait complete!");
 
@@ -28,7 +31,7 @@ fn main() -> anyhow::Result<()> {
         jitter: Some(Duration::from_millis(500)),
     };
 
-    wait_with_jitter.wait()?;
+    wait_with_jitter.wait(&ctx)?;
 
     println!("Wait with jitter complete!");
 
@@ -7,8 +7,11 @@ use std::time::Duration;
 use dozr::conditions::{
     ExponentialWait, GammaWait, NormalWait, UniformWait, WaitCondition,
 };
+use dozr::context::WaitContext;
 
 fn main() -> anyhow::Result<()> {
+    let ctx = WaitContext::system();
+
     // Normal distribution: mean of 500ms, std dev of 100ms
     println!("Sampling from Normal distribution (mean=500ms, std_dev=0.1)...");
     let normal = NormalWait {
@@ -17,7 +20,7 @@ fn main() -> anyhow::Result<()> {
         verbose: None,
         jitter: None,
     };
-    let duration = normal.calculate_wait_duration()?;
+    let duration = normal.calculate_wait_duration(&ctx)?;
     println!("  Sampled duration: {:?}\n", duration);
 
     // Uniform distribution: between 200ms and 800ms
@@ -28,7 +31,7 @@ fn main() -> anyhow::Result<()> {
         verbose: None,
         jitter: None,
     };
-    let duration = uniform.calculate_wait_duration()?;
+    let duration = uniform.calculate_wait_duration(&ctx)?;
     println!("  Sampled duration: {:?}\n", duration);
 
     // Exponential distribution: lambda = 2.0
@@ -38,7 +41,7 @@ fn main() -> anyhow::Result<()> {
         verbose: None,
         jitter: None,
     };
-    let duration = exponential.calculate_wait_duration()?;
+    let duration = exponential.calculate_wait_duration(&ctx)?;
     println!("  Sampled duration: {:?}\n", duration);
 
     // Gamma distribution: shape = 2.0, scale = 0.5
@@ -49,7 +52,7 @@ fn main() -> anyhow::Result<()> {
         verbose: None,
         jitter: None,
     };
-    let duration = gamma.calculate_wait_duration()?;
+    let duration = gamma.calculate_wait_duration(&ctx)?;
     println!("  Sampled duration: {:?}\n", duration);
 
     Ok(())
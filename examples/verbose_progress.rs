@@ -4,14 +4,18 @@
 
 use std::time::Duration;
 
+use dozr::context::WaitContext;
 use dozr::{adaptive_verbose_wait, verbose_wait};
 
-fn main() {
+fn main() -> Result<(), dozr::context::CancelledError> {
+    let ctx = WaitContext::system();
+
     // Fixed update period verbose wait
     println!("Verbose wait with 500ms update period (3 seconds total):");
     println!("---");
 
     verbose_wait(
+        &ctx,
         Duration::from_secs(3),
         Duration::from_millis(500),
         |remaining| {
@@ -21,7 +25,7 @@ fn main() {
                 println!("  Time remaining: {:.1}s", remaining.as_secs_f64());
             }
         },
-    );
+    )?;
 
     println!();
 
@@ -29,11 +33,11 @@ fn main() {
     println!("Adaptive verbose wait (5 seconds total):");
     println!("---");
 
-    adaptive_verbose_wait(Duration::from_secs(5), |remaining| {
+    adaptive_verbose_wait(&ctx, Duration::from_secs(5), |remaining| {
         if remaining.is_zero() {
             println!("  Complete!");
         } else {
             println!("  Time remaining: {}s", remaining.as_secs());
         }
-    });
+    })
 }
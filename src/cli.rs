@@ -1,34 +1,177 @@
-use chrono::{Duration as ChronoDuration, Local, NaiveTime, Timelike};
-use crate::conditions::{self, WaitCondition};
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use crate::conditions::{
+    self, Alignment, BackoffStrategy, CalendarUnit, LogNormalParams, OverlapPolicy, RepeatMode, TimeTarget, WaitCondition,
+};
+use crate::cron::CronSchedule;
+use crate::events::{OutputFormat, PrintFormat};
+use crate::progress::{ProgressStyle, Template};
+use chrono_tz::Tz;
+use clap::error::ErrorKind;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Id, Parser, Subcommand};
+use std::ffi::OsString;
+use std::path::PathBuf;
 use std::time::Duration;
 
-fn parse_time_until(s: &str) -> Result<Duration, String> {
-    let now = Local::now();
-    let parsed_time = NaiveTime::parse_from_str(s, "%H:%M")
-        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
-        .map_err(|_| format!("Invalid time format. Expected HH:MM or HH:MM:SS: {s}"))?;
-
-    let mut target_datetime = now
-        .with_hour(parsed_time.hour())
-        .and_then(|dt| dt.with_minute(parsed_time.minute()))
-        .and_then(|dt| dt.with_second(parsed_time.second()))
-        .and_then(|dt| dt.with_nanosecond(parsed_time.nanosecond()))
-        .unwrap(); // These unwraps are safe as we are setting valid time components
-
-    // If the target time has already passed today, set it for tomorrow
-    if target_datetime < now {
-        target_datetime += ChronoDuration::days(1);
+fn parse_time_target(s: &str) -> Result<TimeTarget, String> {
+    let invalid = || {
+        format!("Invalid time format. Expected HH:MM[:SS], YYYY-MM-DD, an ISO 8601 date and time, or @EPOCH_SECONDS: {s}")
+    };
+
+    if let Some(epoch) = s.strip_prefix('@') {
+        let secs: i64 = epoch.parse().map_err(|_| invalid())?;
+        return DateTime::from_timestamp(secs, 0).map(TimeTarget::Instant).ok_or_else(invalid);
+    }
+    if let Ok(time) = NaiveTime::parse_from_str(s, "%H:%M").or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S")) {
+        return Ok(TimeTarget::TimeOfDay(time));
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Ok(TimeTarget::Instant(datetime.to_utc()));
+    }
+    // ISO 8601 also allows an offset without seconds, which RFC 3339 does not.
+    for format in ["%Y-%m-%dT%H:%M%#z", "%Y-%m-%d %H:%M%#z"] {
+        if let Ok(datetime) = DateTime::parse_from_str(s, format) {
+            return Ok(TimeTarget::Instant(datetime.to_utc()));
+        }
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(TimeTarget::LocalDateTime(datetime));
+        }
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(TimeTarget::Date)
+        .map_err(|_| invalid())
+}
+
+fn parse_tz(s: &str) -> Result<Tz, String> {
+    s.parse().map_err(|_| format!("Unknown time zone. Expected an IANA name such as Europe/London: {s}"))
+}
+
+fn parse_probability(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(probability) if (0.0..=1.0).contains(&probability) => Ok(probability),
+        _ => Err(format!("Invalid probability. Expected a number from 0.0 to 1.0: {s}")),
+    }
+}
+
+/// Parses a distribution parameter given as a duration (e.g., "1500ms") or, as
+/// before durations were accepted, a bare number of seconds (e.g., "1.5").
+fn parse_secs(s: &str) -> Result<f64, String> {
+    if let Ok(secs) = s.parse::<f64>() {
+        return Ok(secs);
+    }
+    humantime::parse_duration(s)
+        .map(|d| d.as_secs_f64())
+        .map_err(|_| format!("Invalid time. Expected a duration such as 1500ms or a number of seconds: {s}"))
+}
+
+fn parse_rate(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("Invalid rate. Expected a number above zero, such as 0.5: {s}")),
     }
+}
+
+fn parse_positive_duration(s: &str) -> Result<Duration, String> {
+    match humantime::parse_duration(s) {
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(format!("Invalid duration. Expected one above zero, such as 500ms: {s}")),
+    }
+}
+
+fn parse_host_port(s: &str) -> Result<String, String> {
+    let (host, port) = s
+        .rsplit_once(':')
+        .ok_or_else(|| format!("Invalid address. Expected HOST:PORT: {s}"))?;
+    if host.is_empty() || port.parse::<u16>().is_err() {
+        return Err(format!("Invalid address. Expected HOST:PORT: {s}"));
+    }
+    Ok(s.to_string())
+}
+
+/// A nested wait, written the same way as a dozr subcommand (e.g., "e 2" or "u 100ms 1s").
+///
+/// A bare duration such as "500ms" is shorthand for "d 500ms".
+#[derive(Debug, Clone)]
+pub struct WaitSpec(pub Box<Commands>);
+
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true)]
+struct WaitSpecParser {
+    #[command(subcommand)]
+    command: Commands,
+}
 
-    let duration_until = target_datetime.signed_duration_since(now);
+fn parse_wait_spec(s: &str) -> Result<WaitSpec, String> {
+    if let Ok(time) = humantime::parse_duration(s) {
+        return Ok(WaitSpec(Box::new(Commands::Duration { time })));
+    }
+    WaitSpecParser::try_parse_from(split_words(s)?)
+        .map(|spec| WaitSpec(Box::new(spec.command)))
+        .map_err(|e| {
+            // The error this one ends up in already suggests --help.
+            let message = e.to_string();
+            let message = message.split("\n\nFor more information").next().unwrap_or_default();
+            format!("Invalid wait specification '{s}': {}", message.trim().trim_start_matches("error: "))
+        })
+}
+
+/// Splits `s` into words like a POSIX shell, so that a word with spaces, such as a
+/// path, can be quoted (e.g., "f '/tmp/my file'").
+fn split_words(s: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Invalid wait specification '{s}': unclosed quote")),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => word.extend(['\\', c]),
+                            None => return Err(format!("Invalid wait specification '{s}': unclosed quote")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Invalid wait specification '{s}': unclosed quote")),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_default().extend(chars.next()),
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
 
-    // Convert chrono::Duration to std::time::Duration
-    duration_until.to_std().map_err(|e| e.to_string())
+/// Parses a nested wait that is slept on between attempts or combined into one
+/// countdown, which has to be timed: a wait for an event has no length to sleep for.
+fn parse_timed_wait_spec(s: &str) -> Result<WaitSpec, String> {
+    let spec = parse_wait_spec(s)?;
+    if !spec.0.clone().into_wait_condition(None, None, None, None).is_timed() {
+        return Err(format!(
+            "Invalid wait specification '{s}': waits for events cannot be slept on; use a duration or a timed wait such as \"e 2\""
+        ));
+    }
+    Ok(spec)
 }
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, override_usage = "dozr [OPTIONS] <COMMAND> [-- <CMD>...]")]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
@@ -43,11 +186,125 @@ pub struct Cli {
     pub verbose: Option<Duration>,
 
     /// Wait only with a certain probability (0.0 to 1.0).
-    #[arg(short, long, global = true)]
+    #[arg(short, long, value_parser = parse_probability, allow_negative_numbers = true, global = true)]
     pub probability: Option<f64>,
+
+    /// Never sample a duration shorter than this from a distribution (e.g., "2s").
+    /// Shorter samples are drawn again, so the distribution keeps its shape above it.
+    #[arg(long, value_name = "TIME", value_parser = humantime::parse_duration, global = true)]
+    pub floor: Option<Duration>,
+
+    /// Never sample a duration longer than this from a distribution (e.g., "1m").
+    /// Longer samples are drawn again, so the distribution keeps its shape below it.
+    #[arg(long, value_name = "TIME", value_parser = humantime::parse_duration, global = true)]
+    pub ceiling: Option<Duration>,
+
+    /// Seed the random number generator, so that sampled durations, jitter and
+    /// probability rolls are the same on every run with the same arguments.
+    #[arg(long, env = "DOZR_SEED", global = true)]
+    pub seed: Option<u64>,
+
+    /// Evaluate times of day, dates, alignment and cron expressions in this IANA time
+    /// zone (e.g., "America/New_York") instead of local time.
+    #[arg(long, value_parser = parse_tz, global = true)]
+    pub tz: Option<Tz>,
+
+    /// A command to run once the wait completes, after "--". It replaces dozr, so its
+    /// exit code and signals pass straight through. For retry, the command to retry.
+    #[arg(last = true, global = true, value_name = "CMD")]
+    pub exec: Vec<OsString>,
+
+    /// Run the command even if --probability decides to skip the wait.
+    #[arg(long, global = true)]
+    pub run_on_skip: bool,
+
+    /// Exit with code 3 instead of 0 if --probability decides to skip the wait.
+    #[arg(long, global = true, conflicts_with = "run_on_skip")]
+    pub fail_on_skip: bool,
+
+    /// How verbose output shows the time remaining: a bar redrawn in place, lines,
+    /// or nothing. Defaults to the bar when stderr is a terminal; "bar" and "lines"
+    /// imply --verbose.
+    #[arg(long, value_enum, global = true)]
+    pub progress: Option<ProgressStyle>,
+
+    /// A format for verbose countdown lines, with the placeholders {remaining},
+    /// {elapsed}, {total}, {percent}, {label}, and {now} and {target} with an optional
+    /// strftime format (e.g., "{now:%T} {label}: {remaining} left ({percent}%)").
+    /// Implies --progress lines unless --progress is given.
+    #[arg(long, value_name = "TEMPLATE", global = true)]
+    pub format: Option<Template>,
+
+    /// Name this wait in verbose lines and JSON events, to tell apart several dozr
+    /// instances writing to one log.
+    #[arg(long, global = true)]
+    pub label: Option<String>,
+
+    /// Report on the wait as human-readable text, or as newline-delimited JSON events
+    /// on stderr for other programs to parse.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub output: OutputFormat,
+
+    /// Print how long the wait would be and exit without waiting or running the
+    /// command.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Print how long each timed wait will be on stdout, then wait.
+    #[arg(long, global = true, conflicts_with = "dry_run")]
+    pub print: bool,
+
+    /// How --print and --dry-run show the wait: as a duration such as "1m 30s",
+    /// seconds, milliseconds, or the RFC 3339 time it ends.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t, global = true)]
+    pub print_format: PrintFormat,
+
+    /// Read control commands from this file or named pipe while waiting, one per line:
+    /// "+5m" or "-30s" to move the deadline, "pause", "resume", "skip" or "status".
+    #[arg(long, value_name = "PATH", global = true)]
+    pub control_file: Option<PathBuf>,
+
+    // Filled in by `Cli::parse_args`.
+    #[arg(skip)]
+    pub invocation: Invocation,
+}
+
+/// The subcommand as it was typed, to describe the wait in JSON events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Invocation {
+    /// The subcommand's full name, even if it was given by an alias.
+    pub command: String,
+    /// The subcommand's own arguments, as given or defaulted, by name.
+    pub params: Vec<(String, Vec<String>)>,
 }
 
-#[derive(Subcommand, Debug)]
+impl Invocation {
+    fn from_matches(matches: &ArgMatches) -> Self {
+        let Some((command, sub)) = matches.subcommand() else {
+            return Self::default();
+        };
+        let cli = Cli::command();
+        let Some(subcommand) = cli.find_subcommand(command) else {
+            return Self::default();
+        };
+        // Skip the global options, and the argument group clap names after the variant.
+        let is_param = |id: &Id| {
+            subcommand.get_arguments().any(|arg| arg.get_id() == id)
+                && !cli.get_arguments().any(|arg| arg.get_id() == id)
+        };
+        let params = sub
+            .ids()
+            .filter(|id| is_param(id))
+            .filter_map(|id| {
+                let values = sub.get_raw(id.as_str())?;
+                Some((id.to_string(), values.map(|v| v.to_string_lossy().into_owned()).collect()))
+            })
+            .collect();
+        Self { command: command.to_string(), params }
+    }
+}
+
+#[derive(Subcommand, Debug, Clone)]
 pub enum Commands {
     /// Wait for a fixed duration
     #[command(alias = "d")]
@@ -62,28 +319,55 @@ pub enum Commands {
         /// Mean of the Normal distribution (e.g., "1s").
         #[arg(value_parser = humantime::parse_duration)]
         mean: Duration,
-        /// Standard deviation of the Normal distribution (e.g., "0.1").
+        /// Standard deviation of the Normal distribution (e.g., "100ms", or "0.1" seconds).
+        #[arg(value_parser = parse_secs)]
         std_dev: f64,
     },
     /// Wait using an exponential distribution
     #[command(alias = "e")]
+    #[command(group(ArgGroup::new("rate").args(["lambda", "mean"]).required(true)))]
     Exponential {
-        /// Lambda (rate parameter) of the Exponential distribution (e.g., "0.5").
-        lambda: f64,
+        /// Lambda (rate parameter) of the Exponential distribution, per second (e.g., "0.5").
+        #[arg(value_parser = parse_rate)]
+        lambda: Option<f64>,
+        /// Mean of the Exponential distribution instead of its rate (e.g., "500ms").
+        #[arg(long, value_parser = parse_positive_duration)]
+        mean: Option<Duration>,
     },
     /// Wait using a log-normal distribution
     #[command(alias = "ln")]
+    #[command(group(ArgGroup::new("spread").args(["cv", "p99"])))]
     LogNormal {
-        /// Mean of the Log-Normal distribution (e.g., "1s").
-        #[arg(value_parser = humantime::parse_duration)]
-        mean: Duration,
-        /// Standard deviation of the Log-Normal distribution (e.g., "0.1").
-        std_dev: f64,
+        /// Mean of the logarithm of the duration in seconds, so that the median is e^MU
+        /// seconds (e.g., "0" for a median of 1s, or "-1" for 368ms). --median or --mean
+        /// is usually easier.
+        #[arg(
+            allow_negative_numbers = true,
+            required_unless_present_any = ["median", "mean"],
+            conflicts_with_all = ["median", "mean", "cv", "p99"],
+            requires = "sigma"
+        )]
+        mu: Option<f64>,
+        /// Standard deviation of the logarithm of the duration (e.g., "0.5").
+        sigma: Option<f64>,
+        /// Median of the Log-Normal distribution, with --cv or --p99 (e.g., "1s").
+        #[arg(long, visible_alias = "p50", value_parser = parse_positive_duration, conflicts_with = "mean", requires = "spread")]
+        median: Option<Duration>,
+        /// Mean of the Log-Normal distribution, with --cv (e.g., "1s").
+        #[arg(long, value_parser = parse_positive_duration, requires = "cv")]
+        mean: Option<Duration>,
+        /// Coefficient of variation: the standard deviation divided by the mean (e.g., "0.5").
+        #[arg(long)]
+        cv: Option<f64>,
+        /// 99th percentile of the Log-Normal distribution, with --median (e.g., "10s").
+        #[arg(long, value_parser = parse_positive_duration, requires = "median", conflicts_with = "mean")]
+        p99: Option<Duration>,
     },
     /// Wait using a Pareto distribution
     #[command(alias = "par")]
     Pareto {
-        /// Scale parameter of the Pareto distribution (e.g., "1.0").
+        /// Scale parameter (minimum) of the Pareto distribution (e.g., "1s", or "1.0" seconds).
+        #[arg(value_parser = parse_secs)]
         scale: f64,
         /// Shape parameter of the Pareto distribution (e.g., "1.5").
         shape: f64,
@@ -101,11 +385,14 @@ pub enum Commands {
     /// Wait using a triangular distribution
     #[command(alias = "t")]
     Triangular {
-        /// Minimum value for the Triangular distribution (e.g., "0.0").
+        /// Minimum value for the Triangular distribution (e.g., "0s", or "0.0" seconds).
+        #[arg(value_parser = parse_secs)]
         min: f64,
-        /// Maximum value for the Triangular distribution (e.g., "1.0").
+        /// Maximum value for the Triangular distribution (e.g., "1s", or "1.0" seconds).
+        #[arg(value_parser = parse_secs)]
         max: f64,
-        /// Mode (most likely value) for the Triangular distribution (e.g., "0.5").
+        /// Mode (most likely value) for the Triangular distribution (e.g., "500ms", or "0.5" seconds).
+        #[arg(value_parser = parse_secs)]
         mode: f64,
     },
     /// Wait using a gamma distribution
@@ -113,103 +400,443 @@ pub enum Commands {
     Gamma {
         /// Shape parameter of the Gamma distribution (e.g., "2.0").
         shape: f64,
-        /// Scale parameter of the Gamma distribution (e.g., "1.0").
+        /// Scale parameter of the Gamma distribution (e.g., "1s", or "1.0" seconds).
+        #[arg(value_parser = parse_secs)]
         scale: f64,
     },
-    /// Align the wait to the next even interval
+    /// Align the wait to the next even interval, or to the start of a day, week or month
     #[command(aliases = &["a", "ali"])]
     Align {
         /// The interval to align to (e.g., "1m", "30s").
-        #[arg(value_parser = humantime::parse_duration)]
-        interval: Duration,
+        #[arg(value_parser = humantime::parse_duration, required_unless_present = "to", conflicts_with = "to")]
+        interval: Option<Duration>,
+        /// Align to the start of the next local day, week (Monday) or month instead of an interval.
+        #[arg(long, value_enum)]
+        to: Option<CalendarUnit>,
+        /// Shift each boundary later by this much (e.g., "5m" with "1h" for five past every hour,
+        /// or "2h30m" with "--to day" for 02:30 each day).
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "0s")]
+        offset: Duration,
     },
-    /// Wait until a specific time of day
+    /// Wait until a specific time of day, date, or date and time
     #[command()]
     At {
-        /// The time to wait until (HH:MM or HH:MM:SS).
-        #[arg(value_parser = parse_time_until)]
-        time: Duration,
+        /// The time to wait until: HH:MM[:SS], YYYY-MM-DD, an ISO 8601 date and time
+        /// (e.g., "2026-10-18T09:00:00+02:00"), or Unix epoch seconds (e.g., "@1760000000").
+        /// A time of day that has already passed today means that time tomorrow.
+        #[arg(value_parser = parse_time_target)]
+        time: TimeTarget,
+        /// Return immediately, instead of failing, if an explicit date has already passed.
+        #[arg(long)]
+        allow_past: bool,
+    },
+    /// Wait until the next time matching a cron expression
+    #[command()]
+    Cron {
+        /// A 5-field (minute hour day month weekday) or 6-field (with leading seconds) cron expression, e.g. "*/15 * * * *".
+        expression: CronSchedule,
+    },
+    /// Wait until a file or directory exists
+    #[command(alias = "f")]
+    FileExists {
+        /// The path to wait for.
+        path: PathBuf,
+        /// Give up after this long (e.g., "30s").
+        #[arg(short, long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+        /// How often to re-check the path (e.g., "500ms"). With inotify this is only a fallback.
+        #[arg(short, long, value_parser = humantime::parse_duration, default_value = "1s")]
+        interval: Duration,
+    },
+    /// Wait until a TCP port accepts connections
+    #[command()]
+    Port {
+        /// The address to connect to (e.g., "localhost:5432").
+        #[arg(value_parser = parse_host_port)]
+        address: String,
+        /// Give up after this long (e.g., "30s").
+        #[arg(short, long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+        /// How long to wait between attempts: a duration (e.g., "500ms") or a wait such as "e 2".
+        #[arg(short, long, value_parser = parse_timed_wait_spec, default_value = "1s")]
+        interval: WaitSpec,
+        /// The longest a single connection attempt may take (e.g., "1s").
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "1s")]
+        connect_timeout: Duration,
+    },
+    /// Wait until one or more processes exit
+    #[command()]
+    Pid {
+        /// The process IDs to wait for.
+        #[arg(required = true)]
+        pids: Vec<u32>,
+        /// Wait until all of the processes have exited (the default).
+        #[arg(long, conflicts_with = "any")]
+        all: bool,
+        /// Wait until any one of the processes has exited.
+        #[arg(long)]
+        any: bool,
+        /// Give up after this long (e.g., "30s").
+        #[arg(short, long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+        /// How often to re-check the processes (e.g., "500ms"). With pidfds this is only a fallback.
+        #[arg(short, long, value_parser = humantime::parse_duration, default_value = "1s")]
+        interval: Duration,
+    },
+    /// Wait for the backoff delay of a given retry attempt
+    #[command(alias = "bo")]
+    Backoff {
+        /// The failed attempt to back off after, counting from 1.
+        #[arg(short, long, default_value_t = 1)]
+        attempt: u32,
+        /// The backoff strategy.
+        #[arg(short, long, value_enum, default_value_t = BackoffStrategy::FullJitter)]
+        strategy: BackoffStrategy,
+        /// The base delay (e.g., "100ms").
+        #[arg(short, long, value_parser = humantime::parse_duration, default_value = "1s")]
+        base: Duration,
+        /// The longest delay (e.g., "30s").
+        #[arg(short, long, value_parser = humantime::parse_duration)]
+        cap: Option<Duration>,
+    },
+    /// Run a command, retrying with backoff until it succeeds
+    #[command(alias = "r")]
+    Retry {
+        /// Give up after this many attempts. Defaults to 5 unless --max-elapsed is given.
+        #[arg(short = 'n', long)]
+        max_attempts: Option<u32>,
+        /// Give up rather than sleep past this much total time (e.g., "5m").
+        #[arg(long, value_parser = humantime::parse_duration)]
+        max_elapsed: Option<Duration>,
+        /// How the delay grows between attempts.
+        #[arg(short, long, value_enum, default_value_t = BackoffStrategy::Exponential)]
+        backoff: BackoffStrategy,
+        /// The base delay between attempts (e.g., "1s").
+        #[arg(short, long, value_parser = humantime::parse_duration, default_value = "1s")]
+        delay: Duration,
+        /// The longest delay between attempts (e.g., "1m").
+        #[arg(long, value_parser = humantime::parse_duration)]
+        max_delay: Option<Duration>,
+        /// Sample each delay from a wait such as "e 0.5" instead of using --backoff.
+        #[arg(short, long, value_parser = parse_timed_wait_spec, conflicts_with_all = ["backoff", "delay", "max_delay"])]
+        wait: Option<WaitSpec>,
+        // Taken from the trailing command by `Cli::parse_args`.
+        #[arg(skip)]
+        command: Vec<OsString>,
+    },
+    /// Run a command repeatedly, waiting before each run
+    #[command()]
+    Every {
+        /// The wait before each run: a duration such as "5m", or any timed wait such as "a 1h" or "e 0.1".
+        #[arg(value_parser = parse_timed_wait_spec)]
+        interval: WaitSpec,
+        /// Stop after this many runs.
+        #[arg(short = 'n', long)]
+        count: Option<u64>,
+        /// Whether the wait starts when a run finishes (fixed-delay) or when it starts (fixed-rate).
+        #[arg(short, long, value_enum, default_value_t = RepeatMode::FixedDelay)]
+        mode: RepeatMode,
+        /// What to do in fixed-rate mode when a run is due while the previous one is still going.
+        #[arg(long, value_enum, default_value_t = OverlapPolicy::Skip)]
+        overlap: OverlapPolicy,
+        // Taken from the trailing command by `Cli::parse_args`.
+        #[arg(skip)]
+        command: Vec<OsString>,
+    },
+    /// Wait for several waits one after another (e.g., seq "u 1s 3s" "a 10s")
+    #[command(alias = "seq")]
+    Sequence {
+        /// The waits, in order: durations such as "5s", or waits such as "a 10s" or "f /tmp/ready".
+        #[arg(required = true, value_parser = parse_wait_spec)]
+        waits: Vec<WaitSpec>,
+    },
+    /// Wait for the longest of several timed waits (e.g., max "a 1m" "n 30s 5")
+    #[command()]
+    Max {
+        /// The timed waits to compare, such as "5s", "a 1m" or "e 2".
+        #[arg(required = true, value_parser = parse_timed_wait_spec)]
+        waits: Vec<WaitSpec>,
+    },
+    /// Wait for the shortest of several timed waits
+    #[command()]
+    Min {
+        /// The timed waits to compare, such as "5s", "a 1m" or "e 2".
+        #[arg(required = true, value_parser = parse_timed_wait_spec)]
+        waits: Vec<WaitSpec>,
+    },
+    /// Wait for the total of several timed waits, each worked out from now
+    #[command()]
+    Sum {
+        /// The timed waits to add up, such as "5s", "u 1s 3s" or "e 2".
+        #[arg(required = true, value_parser = parse_timed_wait_spec)]
+        waits: Vec<WaitSpec>,
+    },
+    /// Wait until the first of several waits finishes (e.g., race "f /tmp/ready" 5m)
+    #[command()]
+    Race {
+        /// The waits to run at once, such as "f /tmp/ready", "port db:5432" or "5m".
+        #[arg(required = true, value_parser = parse_wait_spec)]
+        waits: Vec<WaitSpec>,
     },
 }
 
 impl Commands {
+    /// Builds the condition, wrapped in a [`conditions::ProbabilisticWait`] if a
+    /// `probability` is given.
     pub fn into_wait_condition(
         self,
         jitter: Option<Duration>,
         verbose: Option<Duration>,
         probability: Option<f64>,
+        tz: Option<Tz>,
     ) -> Box<dyn WaitCondition> {
+        let condition = self.into_base_condition(jitter, verbose, tz);
+        match probability {
+            Some(probability) => Box::new(conditions::ProbabilisticWait {
+                inner: condition,
+                probability,
+                verbose,
+            }),
+            None => condition,
+        }
+    }
+
+    fn into_base_condition(self, jitter: Option<Duration>, verbose: Option<Duration>, tz: Option<Tz>) -> Box<dyn WaitCondition> {
         match self {
-            Commands::Duration { time } => {
-                if let Some(probability) = probability {
-                    Box::new(conditions::ProbabilisticWait {
-                        duration: time,
-                        probability,
-                        verbose: verbose,
-                    })
-                } else {
-                    Box::new(conditions::DurationWait {
-                        duration: time,
-                        jitter: jitter,
-                        verbose: verbose,
-                    })
-                }
-            }
+            Commands::Duration { time } => Box::new(conditions::DurationWait {
+                duration: time,
+                jitter,
+                verbose,
+            }),
             Commands::Normal { mean, std_dev } => Box::new(conditions::NormalWait {
                 mean,
                 std_dev,
-                verbose: verbose,
-                jitter: jitter,
+                verbose,
+                jitter,
             }),
-            Commands::Exponential { lambda } => Box::new(conditions::ExponentialWait {
-                lambda,
-                verbose: verbose,
-                jitter: jitter,
+            Commands::Exponential { lambda, mean } => Box::new(conditions::ExponentialWait {
+                lambda: lambda
+                    .or(mean.map(|mean| 1.0 / mean.as_secs_f64()))
+                    .expect("clap requires LAMBDA or --mean"),
+                verbose,
+                jitter,
             }),
-            Commands::LogNormal { mean, std_dev } => Box::new(conditions::LogNormalWait {
-                mean,
-                std_dev,
-                verbose: verbose,
-                jitter: jitter,
+            Commands::LogNormal { mu, sigma, median, mean, cv, p99 } => Box::new(conditions::LogNormalWait {
+                params: match (median, mean, p99) {
+                    (Some(p50), _, Some(p99)) => LogNormalParams::Percentiles { p50, p99 },
+                    (Some(median), _, None) => LogNormalParams::Median { median, cv: cv.unwrap_or_default() },
+                    (None, Some(mean), _) => LogNormalParams::Mean { mean, cv: cv.unwrap_or_default() },
+                    (None, None, _) => LogNormalParams::Log {
+                        mu: mu.unwrap_or_default(),
+                        sigma: sigma.unwrap_or_default(),
+                    },
+                },
+                verbose,
+                jitter,
             }),
             Commands::Pareto { scale, shape } => Box::new(conditions::ParetoWait {
                 scale,
                 shape,
-                verbose: verbose,
-                jitter: jitter,
+                verbose,
+                jitter,
             }),
             Commands::Triangular { min, max, mode } => Box::new(conditions::TriangularWait {
                 min,
                 max,
                 mode,
-                verbose: verbose,
-                jitter: jitter,
+                verbose,
+                jitter,
             }),
-            Commands::Align { interval } => Box::new(conditions::TimeAlignWait {
-                align_interval: interval,
-                verbose: verbose,
+            Commands::Align { interval, to, offset } => Box::new(conditions::TimeAlignWait {
+                alignment: match to {
+                    Some(unit) => Alignment::Calendar(unit),
+                    None => Alignment::Interval(interval.unwrap_or_default()),
+                },
+                offset,
+                tz,
+                verbose,
             }),
             Commands::Uniform { min, max } => Box::new(conditions::UniformWait {
                 min,
                 max,
-                verbose: verbose,
-                jitter: jitter,
+                verbose,
+                jitter,
             }),
-            Commands::At { time } => Box::new(conditions::UntilTimeWait {
-                sleep_duration: time,
-                verbose: verbose,
+            Commands::At { time, allow_past } => Box::new(conditions::UntilTimeWait {
+                target: time,
+                allow_past,
+                tz,
+                verbose,
             }),
             Commands::Gamma { shape, scale } => Box::new(conditions::GammaWait {
                 shape,
                 scale,
-                verbose: verbose,
-                jitter: jitter,
+                verbose,
+                jitter,
+            }),
+            Commands::Cron { expression } => Box::new(conditions::CronWait {
+                schedule: expression,
+                tz,
+                verbose,
+            }),
+            Commands::FileExists { path, timeout, interval } => Box::new(conditions::FileExistsWait {
+                path,
+                poll_interval: interval,
+                timeout,
+                verbose,
+            }),
+            Commands::Port { address, timeout, interval, connect_timeout } => Box::new(conditions::PortWait {
+                address,
+                poll_interval: interval.into_wait_condition(jitter, tz),
+                connect_timeout,
+                timeout,
+                verbose,
+            }),
+            Commands::Pid { pids, all: _, any, timeout, interval } => Box::new(conditions::ProcessExitWait {
+                pids,
+                mode: if any { conditions::ProcessExitMode::Any } else { conditions::ProcessExitMode::All },
+                poll_interval: interval,
+                timeout,
+                verbose,
+            }),
+            Commands::Backoff { attempt, strategy, base, cap } => Box::new(conditions::BackoffWait {
+                backoff: conditions::StrategyBackoff {
+                    strategy,
+                    base,
+                    cap,
+                    jitter,
+                },
+                attempt,
+                verbose,
+            }),
+            Commands::Retry {
+                max_attempts,
+                max_elapsed,
+                backoff,
+                delay,
+                max_delay,
+                wait,
+                command,
+            } => Box::new(conditions::RetryWait {
+                command,
+                backoff: match wait {
+                    Some(wait) => Box::new(conditions::ConditionBackoff(wait.into_wait_condition(jitter, tz))),
+                    None => Box::new(conditions::StrategyBackoff {
+                        strategy: backoff,
+                        base: delay,
+                        cap: max_delay,
+                        jitter,
+                    }),
+                },
+                max_attempts: max_attempts.or(if max_elapsed.is_none() { Some(5) } else { None }),
+                max_elapsed,
+                verbose,
+            }),
+            Commands::Every {
+                interval,
+                count,
+                mode,
+                overlap,
+                command,
+            } => Box::new(conditions::RepeatWait {
+                command,
+                interval: interval.into_wait_condition(jitter, tz),
+                count,
+                mode,
+                overlap,
+                verbose,
+            }),
+            Commands::Sequence { waits } => Box::new(conditions::Sequence {
+                steps: WaitSpec::into_wait_conditions(waits, jitter, verbose, tz),
+                verbose,
+            }),
+            Commands::Max { waits } => Box::new(conditions::Max {
+                waits: WaitSpec::into_wait_conditions(waits, jitter, None, tz),
+                verbose,
+            }),
+            Commands::Min { waits } => Box::new(conditions::Min {
+                waits: WaitSpec::into_wait_conditions(waits, jitter, None, tz),
+                verbose,
+            }),
+            Commands::Sum { waits } => Box::new(conditions::Sum {
+                waits: WaitSpec::into_wait_conditions(waits, jitter, None, tz),
+                verbose,
+            }),
+            Commands::Race { waits } => Box::new(conditions::Race {
+                waits: WaitSpec::into_wait_conditions(waits, jitter, None, tz),
+                verbose,
             }),
         }
     }
 }
 
+impl WaitSpec {
+    /// Builds the nested condition with the global `--jitter` and `--tz`. `--verbose`
+    /// and `--probability` apply to the wait it is part of instead.
+    pub fn into_wait_condition(self, jitter: Option<Duration>, tz: Option<Tz>) -> Box<dyn WaitCondition> {
+        self.0.into_wait_condition(jitter, None, None, tz)
+    }
+
+    /// Builds the waits of a combinator with the global `--jitter` and `--tz`, and
+    /// `verbose` output for those that run on their own.
+    fn into_wait_conditions(
+        waits: Vec<WaitSpec>,
+        jitter: Option<Duration>,
+        verbose: Option<Duration>,
+        tz: Option<Tz>,
+    ) -> Vec<Box<dyn WaitCondition>> {
+        waits.into_iter().map(|wait| wait.0.into_wait_condition(jitter, verbose, None, tz)).collect()
+    }
+}
+
 impl Cli {
+    /// Parses the process arguments, exiting with a usage error if they are invalid.
+    ///
+    /// `retry` and `every` run the trailing command themselves, so it is moved into
+    /// the subcommand rather than run after the wait.
+    pub fn parse_args() -> Self {
+        let matches = Self::command().get_matches();
+        let mut cli = Self::from_arg_matches(&matches).unwrap_or_else(|err| err.format(&mut Self::command()).exit());
+        cli.invocation = Invocation::from_matches(&matches);
+        if let (Some(floor), Some(ceiling)) = (cli.floor, cli.ceiling) {
+            if floor > ceiling {
+                Self::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        format!(
+                            "--floor ({}) must not be greater than --ceiling ({})",
+                            humantime::format_duration(floor),
+                            humantime::format_duration(ceiling)
+                        ),
+                    )
+                    .exit();
+            }
+        }
+        if cli.dry_run && !cli.command.clone().into_wait_condition(None, None, None, None).is_timed() {
+            Self::command()
+                .error(ErrorKind::ArgumentConflict, "--dry-run only prints timed waits; waits for events have no fixed length")
+                .exit();
+        }
+        let (name, command) = match &mut cli.command {
+            Commands::Retry { command, .. } => ("retry", command),
+            Commands::Every { command, .. } => ("every", command),
+            _ => return cli,
+        };
+        if cli.exec.is_empty() {
+            let mut cmd = Self::command();
+            cmd.build();
+            cmd.find_subcommand_mut(name)
+                .expect("the subcommand exists")
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "the following required arguments were not provided:\n  -- <CMD>...",
+                )
+                .exit();
+        }
+        *command = std::mem::take(&mut cli.exec);
+        cli
+    }
+
     pub fn is_adaptive_verbose(&self) -> bool {
         self.verbose == Some(Duration::from_nanos(1))
     }
@@ -230,14 +857,26 @@ impl Cli {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Local;
+    use crate::context::WaitContext;
+    use chrono::{Duration as ChronoDuration, Local, Utc};
+
+    fn duration_until(s: &str) -> Duration {
+        conditions::UntilTimeWait {
+            target: parse_time_target(s).unwrap(),
+            allow_past: false,
+            tz: None,
+            verbose: None,
+        }
+        .calculate_wait_duration(&WaitContext::system())
+        .unwrap()
+    }
 
     #[test]
     fn test_parse_time_until_in_future() {
         let now = Local::now();
         let future_time = now + ChronoDuration::minutes(1);
         let time_str = future_time.format("%H:%M:%S").to_string();
-        let duration = parse_time_until(&time_str).unwrap();
+        let duration = duration_until(&time_str);
         assert!(duration > Duration::from_secs(50) && duration <= Duration::from_secs(60));
     }
 
@@ -246,15 +885,70 @@ mod tests {
         let now = Local::now();
         let past_time = now - ChronoDuration::minutes(1);
         let time_str = past_time.format("%H:%M:%S").to_string();
-        let duration = parse_time_until(&time_str).unwrap();
+        let duration = duration_until(&time_str);
         // Should be almost 24 hours
         assert!(duration > Duration::from_secs(23 * 3600));
     }
 
     #[test]
     fn test_parse_time_invalid_format() {
-        assert!(parse_time_until("invalid-time").is_err());
-        assert!(parse_time_until("25:00").is_err());
-        assert!(parse_time_until("10:65").is_err());
+        assert!(parse_time_target("invalid-time").is_err());
+        assert!(parse_time_target("25:00").is_err());
+        assert!(parse_time_target("10:65").is_err());
+        assert!(parse_time_target("2026-13-01").is_err());
+        assert!(parse_time_target("@soon").is_err());
+    }
+
+    #[test]
+    fn test_parse_time_target_formats() {
+        let instant = |s: &str| TimeTarget::Instant(DateTime::parse_from_rfc3339(s).unwrap().to_utc());
+        assert_eq!(parse_time_target("2026-10-18T09:00:00+02:00").unwrap(), instant("2026-10-18T07:00:00Z"));
+        assert_eq!(parse_time_target("2026-10-18 09:00:00Z").unwrap(), instant("2026-10-18T09:00:00Z"));
+        assert_eq!(parse_time_target("2026-10-18T09:00+0200").unwrap(), instant("2026-10-18T07:00:00Z"));
+        assert_eq!(
+            parse_time_target("@1760000000").unwrap(),
+            TimeTarget::Instant(DateTime::from_timestamp(1_760_000_000, 0).unwrap())
+        );
+        assert_eq!(
+            parse_time_target("2026-10-18T09:30").unwrap(),
+            TimeTarget::LocalDateTime(NaiveDate::from_ymd_opt(2026, 10, 18).unwrap().and_hms_opt(9, 30, 0).unwrap())
+        );
+        assert_eq!(
+            parse_time_target("2026-10-18").unwrap(),
+            TimeTarget::Date(NaiveDate::from_ymd_opt(2026, 10, 18).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_split_words() {
+        let words = |s: &str| split_words(s).unwrap();
+        assert_eq!(words("  e   2 "), ["e", "2"]);
+        assert_eq!(words("f '/tmp/my file' -t 1s"), ["f", "/tmp/my file", "-t", "1s"]);
+        assert_eq!(words(r#"f "a \"b\" c\d""#), ["f", r#"a "b" c\d"#]);
+        assert_eq!(words(r"f a\ b ''"), ["f", "a b", ""]);
+        assert_eq!(split_words("f 'open").unwrap_err(), "Invalid wait specification 'f 'open': unclosed quote");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_wait_spec_errors_name_the_argument() {
+        let err = parse_wait_spec("n 1s").unwrap_err();
+        assert!(err.starts_with("Invalid wait specification 'n 1s': the following required arguments"), "{err}");
+        assert!(err.contains("<STD_DEV>") && !err.contains("For more information"), "{err}");
+    }
+
+    #[test]
+    fn test_explicit_time_in_past() {
+        let past = (Utc::now() - ChronoDuration::minutes(1)).to_rfc3339();
+        let mut wait = conditions::UntilTimeWait {
+            target: parse_time_target(&past).unwrap(),
+            allow_past: false,
+            tz: None,
+            verbose: None,
+        };
+        let ctx = WaitContext::system();
+        assert!(wait.calculate_wait_duration(&ctx).is_err());
+
+        wait.allow_past = true;
+        assert_eq!(wait.calculate_wait_duration(&ctx).unwrap(), Duration::ZERO);
+    }
+}
@@ -1,42 +1,319 @@
-use crate::{adaptive_verbose_wait, verbose_wait};
-use anyhow::Result;
+use crate::context::{CancellationToken, CancelledError, Control, VirtualClock, WaitContext};
+use crate::cron::CronSchedule;
+use crate::events::{JsonReporter, emit, emit_start, prints_text, secs};
+use crate::platform::{PathWatcher, ProcessWatcher};
+use crate::progress::{Bar, BAR_REFRESH, Lines, Progress, ProgressStyle, Reporter, StatusLine};
+use crate::{adaptive_sleep_duration, countdown_wait, get_adaptive_update_period};
+#[cfg(feature = "tokio")]
+use crate::countdown_wait_async;
+use anyhow::{Context, Result};
 use rand::Rng;
-use rand::rngs::ThreadRng;
+use serde_json::json;
 use rand_distr::{Distribution, Normal, Exp, LogNormal, Pareto, Triangular, Uniform, Gamma};
 
-use chrono::{Local, DateTime};
-
-use std::time::{Duration, SystemTime};
+use chrono::{
+    Datelike, DateTime, Days, Duration as ChronoDuration, FixedOffset, Local, LocalResult, Months, NaiveDate, NaiveDateTime,
+    NaiveTime, Offset, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::fmt;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
 
 /// Helper function to perform a wait with optional verbose output.
 ///
 /// - If `verbose` is `None`, performs a simple sleep with no output.
 /// - If `verbose` is `Some(duration)` where duration is 1ns, uses adaptive verbose output.
 /// - Otherwise, uses fixed-interval verbose output with the specified update period.
-fn perform_wait(sleep_duration: Duration, verbose: Option<Duration>) {
-    match verbose {
-        None => {
-            std::thread::sleep(sleep_duration);
-        }
-        Some(update_period) => {
-            let is_adaptive = update_period.as_nanos() == 1;
-            let display_fn = |remaining: Duration| {
-                let now: DateTime<Local> = Local::now();
-                if remaining.is_zero() {
-                    eprintln!("[{}] Wait complete.", now.format("%H:%M:%S"));
-                } else {
-                    eprintln!("[{}] [DOZR] Time remaining: {:.0}s", now.format("%H:%M:%S"), remaining.as_secs_f64());
+///
+/// Verbose output takes the context's [`ProgressStyle`]; the bar is redrawn every
+/// [`BAR_REFRESH`] whatever the update period. With JSON events, the wait emits
+/// those instead, with a `tick` for each update if verbose. If the context has a
+/// print format, the length of the wait is printed on stdout first.
+///
+/// [`Control`]s sent to the context move the deadline in every case, and
+/// [`Control::Status`] prints the time remaining even without verbose output.
+/// Returns a [`CancelledError`] with the time left if the context is cancelled first.
+fn perform_wait(ctx: &WaitContext, sleep_duration: Duration, verbose: Option<Duration>) -> Result<()> {
+    print_length(ctx, sleep_duration)?;
+    let (mut reporter, period) = countdown(ctx, verbose);
+    let result = countdown_wait(ctx, sleep_duration, reporter.as_mut(), period, |duration| ctx.sleep(duration));
+    interrupted(ctx, verbose, result)
+}
+
+/// Like [`perform_wait`], without blocking the thread.
+#[cfg(feature = "tokio")]
+async fn perform_wait_async(ctx: &WaitContext, sleep_duration: Duration, verbose: Option<Duration>) -> Result<()> {
+    print_length(ctx, sleep_duration)?;
+    let (mut reporter, period) = countdown(ctx, verbose);
+    let result = countdown_wait_async(ctx, sleep_duration, reporter.as_mut(), period).await;
+    interrupted(ctx, verbose, result)
+}
+
+/// What reports a timed wait's countdown, and how long it sleeps between updates.
+type Countdown<'a> = (Box<dyn Reporter + Send + 'a>, Box<dyn FnMut(Duration) -> Duration + Send + 'a>);
+
+/// Picks the reporter and update period for [`perform_wait`].
+fn countdown(ctx: &WaitContext, verbose: Option<Duration>) -> Countdown<'_> {
+    let period = |update_period: Duration| -> Box<dyn FnMut(Duration) -> Duration + Send> {
+        if update_period.as_nanos() == 1 {
+            Box::new(adaptive_sleep_duration)
+        } else {
+            Box::new(move |remaining: Duration| update_period.min(remaining))
+        }
+    };
+    match (verbose, ctx.progress()) {
+        _ if ctx.json_events().is_some() => {
+            let reporter = Box::new(JsonReporter::new(ctx, verbose.is_some()));
+            match verbose {
+                None => (reporter, Box::new(|remaining| remaining)),
+                Some(update_period) => (reporter, period(update_period)),
+            }
+        }
+        (None, _) | (Some(_), ProgressStyle::None) => (Box::new(StatusLine::new(ctx, ())), Box::new(|remaining| remaining)),
+        (Some(_), ProgressStyle::Bar) => (Box::new(Bar::new(ctx)), Box::new(|remaining: Duration| BAR_REFRESH.min(remaining))),
+        (Some(update_period), ProgressStyle::Lines) => {
+            let lines = StatusLine::new(ctx, Lines::new(move |progress: &Progress| display_progress(ctx, progress)));
+            (Box::new(lines), period(update_period))
+        }
+    }
+}
+
+/// Prints the length of the wait on stdout, if the context has a print format.
+fn print_length(ctx: &WaitContext, sleep_duration: Duration) -> Result<()> {
+    if let Some(format) = ctx.print_format() {
+        println!("{}", format.format(sleep_duration, ctx.clock().now())?);
+    }
+    Ok(())
+}
+
+/// Reports a countdown that was cancelled, for verbose output.
+fn interrupted(ctx: &WaitContext, verbose: Option<Duration>, result: Result<(), CancelledError>) -> Result<()> {
+    result.map_err(|err| {
+        if prints_text(ctx, verbose) {
+            eprintln!(
+                "[{}] {} Interrupted with {:.0}s remaining",
+                timestamp(ctx),
+                tag(ctx),
+                err.remaining.unwrap_or_default().as_secs_f64()
+            );
+        }
+        err.into()
+    })
+}
+
+/// Prints the time remaining, for verbose output.
+fn display_remaining(ctx: &WaitContext, remaining: Duration) {
+    if remaining.is_zero() {
+        display_complete(ctx);
+    } else {
+        eprintln!("[{}] {} Time remaining: {:.0}s", timestamp(ctx), tag(ctx), remaining.as_secs_f64());
+    }
+}
+
+/// Prints a verbose countdown line, in the context's template if it has one.
+fn display_progress(ctx: &WaitContext, progress: &Progress) {
+    match ctx.template() {
+        Some(template) => {
+            let now = ctx.clock().now().with_timezone(&Local);
+            eprintln!("{}", template.render(progress, now, ctx.label()));
+        }
+        None => display_remaining(ctx, progress.remaining),
+    }
+}
+
+/// Prints that the wait is over, for verbose output.
+fn display_complete(ctx: &WaitContext) {
+    match ctx.label() {
+        Some(_) => eprintln!("[{}] {} Wait complete.", timestamp(ctx), tag(ctx)),
+        None => eprintln!("[{}] Wait complete.", timestamp(ctx)),
+    }
+}
+
+/// The current local time of day on the context's clock, for verbose output.
+pub(crate) fn timestamp(ctx: &WaitContext) -> impl fmt::Display {
+    ctx.clock().now().with_timezone(&Local).format("%H:%M:%S")
+}
+
+/// The tag on dozr's verbose lines, followed by the wait's label if it has one.
+pub(crate) fn tag(ctx: &WaitContext) -> String {
+    match ctx.label() {
+        Some(label) => format!("[DOZR] [{label}]"),
+        None => "[DOZR]".to_string(),
+    }
+}
+
+/// Helper function to wait for an event-driven condition with optional verbose output.
+///
+/// `check` is called until it reports that the condition holds. Between checks,
+/// `block` is handed the longest period it may wait before the next check (to keep
+/// verbose output and the timeout on schedule); it decides how long to actually wait,
+/// and may return early, e.g. when woken by the kernel.
+///
+/// Verbose output reports the time spent waiting so far, since the total is unknown.
+/// Returns a [`TimeoutError`] if `timeout` elapses first, or a [`CancelledError`] if
+/// the context is cancelled.
+fn perform_poll<C, B>(
+    ctx: &WaitContext,
+    description: &str,
+    timeout: Option<Duration>,
+    verbose: Option<Duration>,
+    mut check: C,
+    mut block: B,
+) -> Result<()>
+where
+    C: FnMut() -> Result<bool>,
+    B: FnMut(Duration) -> Result<()>,
+{
+    let mut poll = Poll::new(ctx, description, timeout, verbose);
+    while let Some(max) = poll.next(check()?)? {
+        block(max).inspect_err(|err| {
+            if err.is::<CancelledError>() {
+                poll.end("interrupted");
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// The bookkeeping shared by [`perform_poll`] and its async counterparts: timeout,
+/// verbose output and how long to block between checks.
+struct Poll<'a> {
+    ctx: &'a WaitContext,
+    description: &'a str,
+    timeout: Option<Duration>,
+    verbose: Option<Duration>,
+    start: std::time::Instant,
+    next_report: Duration,
+}
+
+impl<'a> Poll<'a> {
+    fn new(ctx: &'a WaitContext, description: &'a str, timeout: Option<Duration>, verbose: Option<Duration>) -> Self {
+        emit_start(ctx, json!({ "description": description, "timeout": timeout.map(secs) }));
+        Self {
+            ctx,
+            description,
+            timeout,
+            verbose,
+            start: ctx.clock().monotonic(),
+            next_report: Duration::ZERO,
+        }
+    }
+
+    /// Handles the result of a check: returns `None` once the condition holds, and
+    /// otherwise the longest period to block before checking again.
+    ///
+    /// [`Control::Skip`] counts as the condition holding, and [`Control::Status`]
+    /// reports the time spent waiting even without verbose output. Other controls
+    /// have no deadline to move and are ignored.
+    fn next(&mut self, satisfied: bool) -> Result<Option<Duration>> {
+        let ctx = self.ctx;
+        let mut skipped = false;
+        for control in ctx.take_controls() {
+            match control {
+                Control::Skip => skipped = true,
+                Control::Status => self.report("status"),
+                _ => {}
+            }
+        }
+        if satisfied || skipped {
+            if prints_text(ctx, self.verbose) {
+                display_complete(ctx);
+            }
+            self.end("complete");
+            return Ok(None);
+        }
+        if let Err(err) = ctx.check_cancelled() {
+            self.end("interrupted");
+            return Err(err.into());
+        }
+
+        let elapsed = ctx.clock().monotonic().duration_since(self.start);
+        if let Some(timeout) = self.timeout {
+            if elapsed >= timeout {
+                self.end("timeout");
+                return Err(TimeoutError {
+                    description: self.description.to_string(),
+                    timeout,
                 }
-            };
-            if is_adaptive {
-                adaptive_verbose_wait(sleep_duration, display_fn);
-            } else {
-                verbose_wait(sleep_duration, update_period, display_fn);
+                .into());
+            }
+        }
+
+        let mut block_for = Duration::MAX;
+        if let Some(update_period) = self.verbose {
+            if elapsed >= self.next_report {
+                self.report("tick");
+                // Adaptive mode backs off as the wait drags on, mirroring how
+                // `adaptive_verbose_wait` slows down for long remaining times.
+                let period = if update_period.as_nanos() == 1 {
+                    get_adaptive_update_period(elapsed)
+                } else {
+                    update_period
+                };
+                self.next_report = elapsed + period;
             }
+            block_for = block_for.min(self.next_report.saturating_sub(elapsed));
+        }
+        if let Some(timeout) = self.timeout {
+            block_for = block_for.min(timeout.saturating_sub(elapsed));
+        }
+
+        Ok(Some(block_for.max(Duration::from_millis(1))))
+    }
+
+    /// Reports the time spent waiting so far, as a line or as a JSON `event`.
+    fn report(&self, event: &str) {
+        let elapsed = self.ctx.clock().monotonic().duration_since(self.start);
+        if self.ctx.json_events().is_some() {
+            emit(self.ctx, event, json!({ "elapsed": secs(elapsed) }));
+        } else {
+            eprintln!(
+                "[{}] {} Waiting for {}: {:.0}s elapsed",
+                timestamp(self.ctx),
+                tag(self.ctx),
+                self.description,
+                elapsed.as_secs_f64()
+            );
         }
     }
+
+    /// Emits the JSON `event` that ends the wait.
+    fn end(&self, event: &str) {
+        let elapsed = self.ctx.clock().monotonic().duration_since(self.start);
+        emit(self.ctx, event, json!({ "elapsed": secs(elapsed) }));
+    }
 }
 
+/// How often event-driven waits that block in the kernel wake to check for cancellation.
+const CANCEL_CHECK_PERIOD: Duration = Duration::from_millis(100);
+
+/// Error returned when an event-driven condition is not met before its timeout.
+#[derive(Debug)]
+pub struct TimeoutError {
+    pub description: String,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "timed out after {} waiting for {}",
+            humantime::format_duration(self.timeout),
+            self.description
+        )
+    }
+}
+
+impl std::error::Error for TimeoutError {}
+
 // 1. Define a dedicated trait for jitter generation.
 // This makes the dependency explicit and easy to mock.
 pub trait JitterGenerator {
@@ -64,9 +341,207 @@ impl<T: Rng> JitterGenerator for RandomJitterGenerator<T> {
     }
 }
 
-pub trait WaitCondition {
-    fn calculate_wait_duration(&self) -> Result<Duration>;
-    fn wait(&self) -> Result<()>;
+/// How many times [`sample_secs`] draws from a distribution before giving up.
+const MAX_SAMPLE_TRIES: u32 = 10_000;
+
+/// Samples `distribution` truncated to the context's bounds, in seconds.
+///
+/// Samples outside the bounds are drawn again instead of being clamped to them, so
+/// that no probability piles up at the bounds and the shape of the distribution
+/// between them is kept. So are samples too long for a [`Duration`], such as `inf`,
+/// and `NaN`.
+fn sample_secs(ctx: &WaitContext, distribution: &impl Distribution<f64>) -> Result<f64> {
+    let (floor, ceiling) = ctx.bounds();
+    ctx.with_rng(|rng| {
+        for _ in 0..MAX_SAMPLE_TRIES {
+            let secs = distribution.sample(rng);
+            if (floor..=ceiling).contains(&secs) && Duration::try_from_secs_f64(secs).is_ok() {
+                return Ok(secs);
+            }
+        }
+        let ceiling = match ceiling {
+            f64::INFINITY => "infinity".to_string(),
+            ceiling => humantime::format_duration(Duration::from_secs_f64(ceiling)).to_string(),
+        };
+        anyhow::bail!(
+            "no sample fell between {} and {ceiling} in {MAX_SAMPLE_TRIES} tries; the bounds are too far into the distribution's tails",
+            humantime::format_duration(Duration::from_secs_f64(floor)),
+        )
+    })
+}
+
+/// How the delay between retries grows with each failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackoffStrategy {
+    /// Always wait the base delay.
+    Constant,
+    /// Wait the base delay multiplied by the attempt number.
+    Linear,
+    /// Double the delay after every attempt, starting from the base delay.
+    Exponential,
+    /// "Full jitter": a uniformly random delay between zero and the capped exponential delay.
+    FullJitter,
+    /// "Equal jitter": half the capped exponential delay, plus a random amount up to the other half.
+    EqualJitter,
+    /// "Decorrelated jitter": a random delay between the base and three times the previous delay.
+    DecorrelatedJitter,
+}
+
+/// Computes the delay to wait before retrying after a failed attempt.
+pub trait Backoff: Send + Sync {
+    /// Returns the delay after the given failed attempt, counting from 1.
+    ///
+    /// `previous` is the delay returned for the previous attempt, or zero before the
+    /// first retry, for strategies that build on their last delay.
+    fn delay(&self, ctx: &WaitContext, attempt: u32, previous: Duration) -> Result<Duration>;
+}
+
+/// A backoff following a [`BackoffStrategy`], optionally capped and jittered.
+///
+/// The jittered strategies follow the AWS Architecture Blog's "Exponential Backoff
+/// and Jitter"; `jitter` adds a further uniform random delay on top of any strategy.
+pub struct StrategyBackoff {
+    pub strategy: BackoffStrategy,
+    pub base: Duration,
+    pub cap: Option<Duration>,
+    pub jitter: Option<Duration>,
+}
+
+impl StrategyBackoff {
+    fn calculate_delay(&self, attempt: u32, previous: Duration, jitter_gen: &mut dyn JitterGenerator) -> Duration {
+        let attempt = attempt.max(1);
+        let capped = |delay: Duration| self.cap.map_or(delay, |cap| delay.min(cap));
+        let exponential = || {
+            let factor = 2u32.checked_pow(attempt - 1).unwrap_or(u32::MAX);
+            capped(self.base.saturating_mul(factor))
+        };
+        let delay = match self.strategy {
+            BackoffStrategy::Constant => capped(self.base),
+            BackoffStrategy::Linear => capped(self.base.saturating_mul(attempt)),
+            BackoffStrategy::Exponential => exponential(),
+            BackoffStrategy::FullJitter => jitter_gen.generate(exponential()),
+            BackoffStrategy::EqualJitter => {
+                let half = exponential() / 2;
+                half + jitter_gen.generate(half)
+            }
+            BackoffStrategy::DecorrelatedJitter => {
+                let previous = previous.max(self.base);
+                let upper = previous.saturating_mul(3);
+                capped(self.base + jitter_gen.generate(upper - self.base))
+            }
+        };
+        delay + jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO))
+    }
+}
+
+impl Backoff for StrategyBackoff {
+    fn delay(&self, ctx: &WaitContext, attempt: u32, previous: Duration) -> Result<Duration> {
+        Ok(ctx.with_rng(|rng| self.calculate_delay(attempt, previous, &mut RandomJitterGenerator::new(rng))))
+    }
+}
+
+/// Waits for the delay a [`StrategyBackoff`] would give a particular retry attempt.
+///
+/// This lets shell loops that track their own attempt counter share dozr's backoff
+/// strategies. Decorrelated jitter depends on the previous delay, so the delays of
+/// the earlier attempts are sampled first.
+pub struct BackoffWait {
+    pub backoff: StrategyBackoff,
+    pub attempt: u32,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for BackoffWait {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        if self.backoff.strategy != BackoffStrategy::DecorrelatedJitter {
+            return self.backoff.delay(ctx, self.attempt, Duration::ZERO);
+        }
+        let mut delay = Duration::ZERO;
+        for attempt in 1..=self.attempt.max(1) {
+            delay = self.backoff.delay(ctx, attempt, delay)?;
+        }
+        Ok(delay)
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Uses any timed wait condition as a backoff, sampling a fresh delay for every retry.
+///
+/// Only the sampled duration is slept for, so a wait for an event, whose
+/// [`WaitCondition::calculate_wait_duration`] is only a bound, makes no sense here.
+pub struct ConditionBackoff(pub Box<dyn WaitCondition>);
+
+impl Backoff for ConditionBackoff {
+    fn delay(&self, ctx: &WaitContext, _attempt: u32, _previous: Duration) -> Result<Duration> {
+        self.0.calculate_wait_duration(ctx)
+    }
+}
+
+/// Whether a wait actually happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    Completed,
+    /// The wait was skipped, e.g. by a probability roll.
+    Skipped,
+}
+
+/// The future returned by the async methods of [`WaitCondition`].
+#[cfg(feature = "tokio")]
+pub type WaitFuture<'a, T = ()> = std::pin::Pin<Box<dyn std::future::Future<Output = Result<T>> + Send + 'a>>;
+
+pub trait WaitCondition: Send + Sync {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration>;
+    fn wait(&self, ctx: &WaitContext) -> Result<()>;
+
+    /// Waits, reporting whether the wait happened or was skipped.
+    fn wait_outcome(&self, ctx: &WaitContext) -> Result<WaitOutcome> {
+        self.wait(ctx).map(|()| WaitOutcome::Completed)
+    }
+
+    /// Whether [`WaitCondition::calculate_wait_duration`] gives the length of the
+    /// wait up front, rather than only a bound on a wait for an event.
+    fn is_timed(&self) -> bool {
+        true
+    }
+
+    /// A line describing parameters the wait derived from the ones it was given,
+    /// shown by verbose waits and `--dry-run`.
+    fn describe(&self) -> Option<String> {
+        None
+    }
+
+    /// Waits without blocking the thread, on a tokio runtime with time and IO enabled.
+    ///
+    /// Dropping the future or cancelling the context's token cancels the wait,
+    /// killing any command it started. The default sleeps for
+    /// [`WaitCondition::calculate_wait_duration`].
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            ctx.sleep_async(self.calculate_wait_duration(ctx)?).await?;
+            Ok(())
+        })
+    }
+
+    /// Like [`WaitCondition::wait_outcome`], without blocking the thread.
+    #[cfg(feature = "tokio")]
+    fn wait_outcome_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a, WaitOutcome> {
+        Box::pin(async move { self.wait_async(ctx).await.map(|()| WaitOutcome::Completed) })
+    }
 }
 
 pub struct DurationWait {
@@ -92,21 +567,30 @@ pub struct NormalWait {
 }
 
 impl WaitCondition for NormalWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
         let mean_secs = self.mean.as_secs_f64();
         let normal = Normal::new(mean_secs, self.std_dev)?;
-        let mut rng = ThreadRng::default();
-        let duration_secs = normal.sample(&mut rng).max(0.0);
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let random_jitter = jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO));
+        let duration_secs = sample_secs(ctx, &normal)?;
+        let random_jitter = ctx.with_rng(|rng| {
+            RandomJitterGenerator::new(rng).generate(self.jitter.unwrap_or(Duration::ZERO))
+        });
         Ok(Duration::from_secs_f64(duration_secs) + random_jitter)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
 }
 
 pub struct ExponentialWait {
@@ -116,45 +600,142 @@ pub struct ExponentialWait {
 }
 
 impl WaitCondition for ExponentialWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
         let exponential = Exp::new(self.lambda)?;
-        let mut rng = ThreadRng::default();
-        let duration_secs = exponential.sample(&mut rng).max(0.0);
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let random_jitter = jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO));
+        let duration_secs = sample_secs(ctx, &exponential)?;
+        let random_jitter = ctx.with_rng(|rng| {
+            RandomJitterGenerator::new(rng).generate(self.jitter.unwrap_or(Duration::ZERO))
+        });
         Ok(Duration::from_secs_f64(duration_secs) + random_jitter)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
+
+/// The 99th percentile of the standard normal distribution.
+const Z_99: f64 = 2.326_347_874_040_841;
+
+/// The ways to give the shape of a log-normal distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogNormalParams {
+    /// The mean `mu` and standard deviation `sigma` of the natural logarithm of the
+    /// duration in seconds, so that the median is `e^mu` seconds.
+    Log { mu: f64, sigma: f64 },
+    /// The median duration, and the coefficient of variation: the standard deviation
+    /// divided by the mean.
+    Median { median: Duration, cv: f64 },
+    /// The mean duration and the coefficient of variation.
+    Mean { mean: Duration, cv: f64 },
+    /// The median and 99th percentile durations.
+    Percentiles { p50: Duration, p99: Duration },
+}
+
+impl LogNormalParams {
+    /// Solves for `mu` and `sigma`, the parameters of the logarithm's normal distribution.
+    pub fn solve(&self) -> Result<(f64, f64)> {
+        let ln_secs = |duration: Duration, name: &str| {
+            if duration.is_zero() {
+                anyhow::bail!("the {name} of a log-normal distribution must be above zero");
+            }
+            Ok(duration.as_secs_f64().ln())
+        };
+        let sigma_for = |cv: f64| {
+            if !(cv.is_finite() && cv >= 0.0) {
+                anyhow::bail!("the coefficient of variation must be a number from 0 up: {cv}");
+            }
+            Ok((1.0 + cv * cv).ln().sqrt())
+        };
+        match *self {
+            LogNormalParams::Log { mu, sigma } => Ok((mu, sigma)),
+            LogNormalParams::Median { median, cv } => Ok((ln_secs(median, "median")?, sigma_for(cv)?)),
+            LogNormalParams::Mean { mean, cv } => {
+                let sigma = sigma_for(cv)?;
+                Ok((ln_secs(mean, "mean")? - sigma * sigma / 2.0, sigma))
+            }
+            LogNormalParams::Percentiles { p50, p99 } => {
+                if p99 < p50 {
+                    anyhow::bail!(
+                        "the 99th percentile ({}) must not be below the median ({})",
+                        humantime::format_duration(p99),
+                        humantime::format_duration(p50)
+                    );
+                }
+                let mu = ln_secs(p50, "median")?;
+                Ok((mu, (ln_secs(p99, "99th percentile")? - mu) / Z_99))
+            }
+        }
+    }
 }
 
 pub struct LogNormalWait {
-    pub mean: Duration,
-    pub std_dev: f64,
+    pub params: LogNormalParams,
     pub verbose: Option<Duration>,
     pub jitter: Option<Duration>,
 }
 
+impl LogNormalWait {
+    /// Prints the solved parameters, so that a verbose wait shows what it samples from.
+    fn print_params(&self, ctx: &WaitContext) {
+        if let Some(description) = self.describe().filter(|_| prints_text(ctx, self.verbose)) {
+            eprintln!("[{}] {} {}", timestamp(ctx), tag(ctx), description);
+        }
+    }
+}
+
 impl WaitCondition for LogNormalWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
-        let mean_secs = self.mean.as_secs_f64();
-        let log_normal = LogNormal::new(mean_secs, self.std_dev)?;
-        let mut rng = ThreadRng::default();
-        let duration_secs = log_normal.sample(&mut rng).max(0.0);
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let random_jitter = jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO));
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        let (mu, sigma) = self.params.solve()?;
+        let log_normal = LogNormal::new(mu, sigma)?;
+        let duration_secs = sample_secs(ctx, &log_normal)?;
+        let random_jitter = ctx.with_rng(|rng| {
+            RandomJitterGenerator::new(rng).generate(self.jitter.unwrap_or(Duration::ZERO))
+        });
         Ok(Duration::from_secs_f64(duration_secs) + random_jitter)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        self.print_params(ctx);
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    fn describe(&self) -> Option<String> {
+        let (mu, sigma) = self.params.solve().ok()?;
+        let secs = |secs: f64| match Duration::try_from_secs_f64(secs) {
+            Ok(duration) => humantime::format_duration(Duration::from_millis(duration.as_millis() as u64)).to_string(),
+            Err(_) => format!("{secs:.3e}s"),
+        };
+        Some(format!(
+            "Log-normal with mu {mu:.3} and sigma {sigma:.3}: median {}, mean {}, 99th percentile {}",
+            secs(mu.exp()),
+            secs((mu + sigma * sigma / 2.0).exp()),
+            secs((mu + Z_99 * sigma).exp()),
+        ))
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            self.print_params(ctx);
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
 }
 
 pub struct ParetoWait {
@@ -165,23 +746,30 @@ pub struct ParetoWait {
 }
 
 impl WaitCondition for ParetoWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
         let pareto = Pareto::new(self.scale, self.shape)?;
-        let mut rng = ThreadRng::default();
-        let duration_secs = pareto.sample(&mut rng).max(0.0);
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let random_jitter = jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO));
+        let duration_secs = sample_secs(ctx, &pareto)?;
+        let random_jitter = ctx.with_rng(|rng| {
+            RandomJitterGenerator::new(rng).generate(self.jitter.unwrap_or(Duration::ZERO))
+        });
         Ok(Duration::from_secs_f64(duration_secs) + random_jitter)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
-}
-
 
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
 
 pub struct UniformWait {
     pub min: Duration,
@@ -191,22 +779,31 @@ pub struct UniformWait {
 }
 
 impl WaitCondition for UniformWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
         let min_secs = self.min.as_secs_f64();
         let max_secs = self.max.as_secs_f64();
         let uniform = Uniform::new(min_secs, max_secs)?;
-        let mut rng = ThreadRng::default();
-        let duration_secs = uniform.sample(&mut rng).max(0.0);
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let random_jitter = jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO));
+        let duration_secs = sample_secs(ctx, &uniform)?;
+        let random_jitter = ctx.with_rng(|rng| {
+            RandomJitterGenerator::new(rng).generate(self.jitter.unwrap_or(Duration::ZERO))
+        });
         Ok(Duration::from_secs_f64(duration_secs) + random_jitter)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
 }
 
 pub struct TriangularWait {
@@ -218,20 +815,29 @@ pub struct TriangularWait {
 }
 
 impl WaitCondition for TriangularWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
         let triangular = Triangular::new(self.min, self.max, self.mode)?;
-        let mut rng = ThreadRng::default();
-        let duration_secs = triangular.sample(&mut rng).max(0.0);
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let random_jitter = jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO));
+        let duration_secs = sample_secs(ctx, &triangular)?;
+        let random_jitter = ctx.with_rng(|rng| {
+            RandomJitterGenerator::new(rng).generate(self.jitter.unwrap_or(Duration::ZERO))
+        });
         Ok(Duration::from_secs_f64(duration_secs) + random_jitter)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
 }
 
 pub struct GammaWait {
@@ -242,343 +848,2524 @@ pub struct GammaWait {
 }
 
 impl WaitCondition for GammaWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
         let gamma = Gamma::new(self.shape, self.scale)?;
-        let mut rng = ThreadRng::default();
-        let duration_secs = gamma.sample(&mut rng).max(0.0);
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let random_jitter = jitter_gen.generate(self.jitter.unwrap_or(Duration::ZERO));
+        let duration_secs = sample_secs(ctx, &gamma)?;
+        let random_jitter = ctx.with_rng(|rng| {
+            RandomJitterGenerator::new(rng).generate(self.jitter.unwrap_or(Duration::ZERO))
+        });
         Ok(Duration::from_secs_f64(duration_secs) + random_jitter)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
+
+/// A calendar period that a [`TimeAlignWait`] can align to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CalendarUnit {
+    /// Midnight.
+    Day,
+    /// Midnight on Monday.
+    Week,
+    /// Midnight on the first of the month.
+    Month,
+}
+
+/// What a [`TimeAlignWait`] aligns to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    /// Multiples of a fixed interval.
+    Interval(Duration),
+    /// The start of each calendar day, week or month.
+    Calendar(CalendarUnit),
 }
 
+/// Waits until the next alignment boundary, shifted by `offset`.
+///
+/// Interval boundaries are counted from the Unix epoch unless a time zone is given,
+/// in which case they are counted on that zone's wall clock, so that "1h" or "1d"
+/// lands on whole local hours or local midnight. Calendar boundaries are always on a
+/// wall clock: that of `tz`, or of the local time zone.
 pub struct TimeAlignWait {
-    pub align_interval: Duration,
+    pub alignment: Alignment,
+    pub offset: Duration,
+    pub tz: Option<Tz>,
     pub verbose: Option<Duration>,
 }
 
+impl TimeAlignWait {
+    /// Returns the first aligned instant strictly after `now`, on the wall clock of
+    /// `now`'s time zone.
+    ///
+    /// A boundary skipped by a daylight saving gap moves forward by the length of the
+    /// gap. A boundary repeated by an overlap is reached once, at its first occurrence
+    /// after `now`.
+    pub fn next_boundary<Z: TimeZone>(&self, now: &DateTime<Z>) -> Result<DateTime<Z>> {
+        if self.alignment == Alignment::Interval(Duration::ZERO) {
+            return Ok(now.clone());
+        }
+
+        let tz = now.timezone();
+        let wall = now.naive_local();
+        let offset = ChronoDuration::from_std(self.offset)?;
+        let mut start = self.period_start(wall - offset)?;
+        loop {
+            let boundary = start + offset;
+            // Boundaries are walked on the wall clock, so one that is repeated by an
+            // overlap is only considered again if it is still ahead of us on that clock.
+            if boundary > wall {
+                match tz.from_local_datetime(&boundary) {
+                    LocalResult::Single(next) => return Ok(next),
+                    LocalResult::Ambiguous(earliest, latest) => {
+                        if earliest > *now {
+                            return Ok(earliest);
+                        } else if latest > *now {
+                            return Ok(latest);
+                        }
+                    }
+                    LocalResult::None => return Ok(resolve_local(&tz, boundary)),
+                }
+            }
+            start = self.next_period(start)?;
+        }
+    }
+
+    /// Returns the start of the period containing the wall-clock time `t`.
+    fn period_start(&self, t: NaiveDateTime) -> Result<NaiveDateTime> {
+        let date = t.date();
+        Ok(match self.alignment {
+            Alignment::Interval(interval) => {
+                let interval_nanos = ChronoDuration::from_std(interval)?
+                    .num_nanoseconds()
+                    .context("alignment interval is too large")?;
+                let nanos = t.and_utc().timestamp_nanos_opt().context("time is out of range")?;
+                t - ChronoDuration::nanoseconds(nanos.rem_euclid(interval_nanos))
+            }
+            Alignment::Calendar(CalendarUnit::Day) => date.and_time(NaiveTime::MIN),
+            Alignment::Calendar(CalendarUnit::Week) => {
+                (date - ChronoDuration::days(date.weekday().num_days_from_monday().into())).and_time(NaiveTime::MIN)
+            }
+            Alignment::Calendar(CalendarUnit::Month) => date.with_day(1).context("invalid date")?.and_time(NaiveTime::MIN),
+        })
+    }
+
+    /// Returns the start of the period after the one starting at `start`.
+    fn next_period(&self, start: NaiveDateTime) -> Result<NaiveDateTime> {
+        let next = match self.alignment {
+            Alignment::Interval(interval) => start.checked_add_signed(ChronoDuration::from_std(interval)?),
+            Alignment::Calendar(CalendarUnit::Day) => start.checked_add_days(Days::new(1)),
+            Alignment::Calendar(CalendarUnit::Week) => start.checked_add_days(Days::new(7)),
+            Alignment::Calendar(CalendarUnit::Month) => start.checked_add_months(Months::new(1)),
+        };
+        next.context("time is out of range")
+    }
+}
+
 impl WaitCondition for TimeAlignWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
-        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
-        let align_interval_nanos = self.align_interval.as_nanos();
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        let now = ctx.clock().now();
+        let next = match (self.tz, self.alignment) {
+            (Some(tz), _) => self.next_boundary(&now.with_timezone(&tz))?.to_utc(),
+            (None, Alignment::Interval(_)) => self.next_boundary(&now)?,
+            (None, Alignment::Calendar(_)) => self.next_boundary(&now.with_timezone(&Local))?.to_utc(),
+        };
+        Ok(next.signed_duration_since(now).to_std()?)
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
 
-        if align_interval_nanos == 0 {
-            return Ok(Duration::ZERO);
+/// Converts a wall-clock time in `tz` to an instant.
+///
+/// Times skipped by a daylight saving gap move forward by the length of the gap (so
+/// 02:30 on a night when clocks jump from 02:00 to 03:00 becomes 03:30), and times
+/// repeated by an overlap resolve to their first occurrence.
+pub fn resolve_local<Z: TimeZone>(tz: &Z, naive: NaiveDateTime) -> DateTime<Z> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t,
+        LocalResult::None => {
+            // Interpret the time with the offset in force before the gap.
+            let before = tz.offset_from_utc_datetime(&(naive - ChronoDuration::days(1))).fix();
+            tz.from_utc_datetime(&(naive - before))
         }
+    }
+}
 
-        let now_nanos = now.as_nanos();
-        let remainder = now_nanos % align_interval_nanos;
+/// Waits until the next instant matching a cron expression.
+///
+/// The schedule is evaluated on the wall clock of `tz`, or of the local time zone
+/// if `tz` is `None`.
+pub struct CronWait {
+    pub schedule: CronSchedule,
+    pub tz: Option<Tz>,
+    pub verbose: Option<Duration>,
+}
 
-        let sleep_duration = if remainder == 0 {
-            self.align_interval
-        } else {
-            Duration::from_nanos((align_interval_nanos - remainder) as u64)
+impl CronWait {
+    /// Returns the next matching instant after `now`.
+    pub fn next_run(&self, now: DateTime<Utc>) -> Result<DateTime<FixedOffset>> {
+        let next = match self.tz {
+            Some(tz) => self.schedule.next_after(&now.with_timezone(&tz)).map(|t| t.fixed_offset()),
+            None => self.schedule.next_after(&now.with_timezone(&Local)).map(|t| t.fixed_offset()),
         };
+        next.ok_or_else(|| anyhow::anyhow!("cron expression never matches"))
+    }
+
+    /// Returns how long to wait for the next run, printing when it is if verbose.
+    fn announce_next_run(&self, ctx: &WaitContext) -> Result<Duration> {
+        let now = ctx.clock().now();
+        let next_run = self.next_run(now)?;
+        if prints_text(ctx, self.verbose) {
+            eprintln!(
+                "[{}] {} Next run at {}",
+                timestamp(ctx),
+                tag(ctx),
+                next_run.format("%Y-%m-%d %H:%M:%S %:z")
+            );
+        }
+        Ok(next_run.signed_duration_since(now).to_std()?)
+    }
+}
 
-        Ok(sleep_duration)
+impl WaitCondition for CronWait {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        let now = ctx.clock().now();
+        Ok(self.next_run(now)?.signed_duration_since(now).to_std()?)
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.announce_next_run(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.announce_next_run(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
 }
 
+/// Runs `inner` with a chance of `probability`, and otherwise skips it.
+///
+/// The roll comes first, so with a seed the inner wait samples the same duration
+/// whether it is run or only calculated.
 pub struct ProbabilisticWait {
-    pub duration: Duration,
+    pub inner: Box<dyn WaitCondition>,
     pub probability: f64,
     pub verbose: Option<Duration>,
 }
 
+/// The target of an [`UntilTimeWait`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeTarget {
+    /// A time of day. If it has already passed today, the same time tomorrow.
+    TimeOfDay(NaiveTime),
+    /// The start (midnight) of a date.
+    Date(NaiveDate),
+    /// A date and time on the wall clock.
+    LocalDateTime(NaiveDateTime),
+    /// An exact instant, e.g. from an RFC 3339 timestamp or Unix epoch seconds.
+    Instant(DateTime<Utc>),
+}
+
+impl TimeTarget {
+    /// Resolves the target to an instant on the wall clock of `now`'s time zone,
+    /// relative to `now` for times of day. See [`resolve_local`] for how daylight
+    /// saving transitions are handled.
+    pub fn resolve<Z: TimeZone>(&self, now: &DateTime<Z>) -> DateTime<Z> {
+        let tz = now.timezone();
+        match *self {
+            TimeTarget::TimeOfDay(time) => {
+                let today = now.date_naive();
+                let target = resolve_local(&tz, today.and_time(time));
+                if target < *now {
+                    resolve_local(&tz, today.succ_opt().unwrap_or(today).and_time(time))
+                } else {
+                    target
+                }
+            }
+            TimeTarget::Date(date) => resolve_local(&tz, date.and_time(NaiveTime::MIN)),
+            TimeTarget::LocalDateTime(datetime) => resolve_local(&tz, datetime),
+            TimeTarget::Instant(instant) => instant.with_timezone(&tz),
+        }
+    }
+}
+
+/// Waits until a particular time, on the wall clock of `tz` or of the local time zone.
+///
+/// A [`TimeTarget::TimeOfDay`] never lies in the past, as it rolls over to the next
+/// day. For other targets in the past, the wait fails unless `allow_past` is set, in
+/// which case it returns immediately.
 pub struct UntilTimeWait {
-    pub sleep_duration: Duration,
+    pub target: TimeTarget,
+    pub allow_past: bool,
+    pub tz: Option<Tz>,
     pub verbose: Option<Duration>,
 }
 
+impl UntilTimeWait {
+    /// Returns how long to wait from `now`.
+    pub fn duration_from<Z: TimeZone>(&self, now: &DateTime<Z>) -> Result<Duration> {
+        let target = self.target.resolve(now);
+        if target >= *now {
+            Ok(target.signed_duration_since(now).to_std()?)
+        } else if self.allow_past {
+            Ok(Duration::ZERO)
+        } else {
+            anyhow::bail!(
+                "target time {} is in the past (use --allow-past to continue immediately)",
+                target.fixed_offset().format("%Y-%m-%d %H:%M:%S %:z")
+            )
+        }
+    }
+}
+
 impl WaitCondition for UntilTimeWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
-        Ok(self.sleep_duration)
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        match self.tz {
+            Some(tz) => self.duration_from(&ctx.clock().now().with_timezone(&tz)),
+            None => self.duration_from(&ctx.clock().now().with_timezone(&Local)),
+        }
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
 }
 
 impl WaitCondition for ProbabilisticWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
-        let mut rng = ThreadRng::default();
-        let roll: f64 = rng.random_range(0.0..1.0);
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        let roll: f64 = ctx.with_rng(|rng| rng.random_range(0.0..1.0));
 
-        if roll <= self.probability {
-            Ok(self.duration)
+        if roll < self.probability {
+            self.inner.calculate_wait_duration(ctx)
         } else {
             Ok(Duration::ZERO)
         }
     }
 
-    fn wait(&self) -> Result<()> {
-        let mut rng = ThreadRng::default();
-        let roll: f64 = rng.random_range(0.0..1.0);
-        let should_sleep = roll <= self.probability;
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        self.wait_outcome(ctx).map(|_| ())
+    }
+
+    fn wait_outcome(&self, ctx: &WaitContext) -> Result<WaitOutcome> {
+        if !self.roll(ctx) {
+            return Ok(WaitOutcome::Skipped);
+        }
+        self.inner.wait_outcome(ctx)
+    }
+
+    fn is_timed(&self) -> bool {
+        self.inner.is_timed()
+    }
+
+    fn describe(&self) -> Option<String> {
+        self.inner.describe()
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move { self.wait_outcome_async(ctx).await.map(|_| ()) })
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_outcome_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a, WaitOutcome> {
+        Box::pin(async move {
+            if !self.roll(ctx) {
+                return Ok(WaitOutcome::Skipped);
+            }
+            self.inner.wait_outcome_async(ctx).await
+        })
+    }
+}
+
+impl ProbabilisticWait {
+    /// Rolls whether to wait, printing the roll if verbose and the wait is skipped.
+    fn roll(&self, ctx: &WaitContext) -> bool {
+        let roll: f64 = ctx.with_rng(|rng| rng.random_range(0.0..1.0));
+        let should_sleep = roll < self.probability;
+
+        if !should_sleep {
+            if prints_text(ctx, self.verbose) {
+                eprintln!(
+                    "Probabilistic wait: Skipping sleep (probability: {}, roll: {:.2})",
+                    self.probability, roll
+                );
+            }
+            emit(ctx, "skip", json!({ "probability": self.probability, "roll": roll }));
+        }
+        should_sleep
+    }
+}
+
+impl WaitCondition for DurationWait {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        Ok(ctx.with_rng(|rng| self.calculate_sleep_duration(&mut RandomJitterGenerator::new(rng))))
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Waits until a file or directory exists.
+///
+/// On Linux the parent directory is watched with inotify so the wait ends as soon
+/// as the path appears; `poll_interval` is then only a safety net. Elsewhere, and
+/// when waiting asynchronously, the path is simply re-checked every `poll_interval`.
+pub struct FileExistsWait {
+    pub path: PathBuf,
+    pub poll_interval: Duration,
+    pub timeout: Option<Duration>,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for FileExistsWait {
+    /// The wait time is unknown up front: this is zero if the path already
+    /// exists, and otherwise the timeout (or [`Duration::MAX`] without one).
+    fn calculate_wait_duration(&self, _ctx: &WaitContext) -> Result<Duration> {
+        if self.path.exists() {
+            Ok(Duration::ZERO)
+        } else {
+            Ok(self.timeout.unwrap_or(Duration::MAX))
+        }
+    }
+
+    fn is_timed(&self) -> bool {
+        false
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let mut watcher = PathWatcher::new();
+        perform_poll(
+            ctx,
+            &self.path.display().to_string(),
+            self.timeout,
+            self.verbose,
+            || Ok(self.path.exists()),
+            |max| {
+                let block_for = self.poll_interval.min(max);
+                match watcher.as_mut() {
+                    Some(watcher) if ctx.clock().is_real_time() => {
+                        watcher.wait(&self.path, block_for.min(CANCEL_CHECK_PERIOD))?
+                    }
+                    _ => ctx.sleep(block_for)?,
+                }
+                Ok(())
+            },
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let description = self.path.display().to_string();
+            let mut poll = Poll::new(ctx, &description, self.timeout, self.verbose);
+            while let Some(max) = poll.next(self.path.exists())? {
+                ctx.sleep_async(self.poll_interval.min(max)).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Waits until a TCP port accepts connections.
+///
+/// The address is re-resolved on every attempt, so it may name a host that does not
+/// exist yet (e.g. a container that is still starting). Between attempts the wait
+/// sleeps for a duration drawn from `poll_interval`, which can be any other condition,
+/// such as a fixed [`DurationWait`] or a randomised [`ExponentialWait`].
+pub struct PortWait {
+    /// The address to connect to, as `host:port`.
+    pub address: String,
+    pub poll_interval: Box<dyn WaitCondition>,
+    /// The longest a single connection attempt may take.
+    pub connect_timeout: Duration,
+    pub timeout: Option<Duration>,
+    pub verbose: Option<Duration>,
+}
+
+impl PortWait {
+    fn try_connect(&self) -> bool {
+        let Ok(addrs) = self.address.to_socket_addrs() else {
+            return false;
+        };
+        addrs
+            .into_iter()
+            .any(|addr| TcpStream::connect_timeout(&addr, self.connect_timeout).is_ok())
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn try_connect_async(&self) -> bool {
+        let Ok(addrs) = tokio::net::lookup_host(&self.address).await else {
+            return false;
+        };
+        for addr in addrs {
+            let connect = tokio::net::TcpStream::connect(addr);
+            if let Ok(Ok(_)) = tokio::time::timeout(self.connect_timeout, connect).await {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl WaitCondition for PortWait {
+    /// The wait time is unknown up front: this is zero if the port accepts a
+    /// connection right now, and otherwise the timeout (or [`Duration::MAX`] without one).
+    fn calculate_wait_duration(&self, _ctx: &WaitContext) -> Result<Duration> {
+        if self.try_connect() {
+            Ok(Duration::ZERO)
+        } else {
+            Ok(self.timeout.unwrap_or(Duration::MAX))
+        }
+    }
+
+    fn is_timed(&self) -> bool {
+        false
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        perform_poll(
+            ctx,
+            &self.address,
+            self.timeout,
+            self.verbose,
+            || Ok(self.try_connect()),
+            |max| {
+                let interval = self.poll_interval.calculate_wait_duration(ctx)?;
+                ctx.sleep(interval.min(max))?;
+                Ok(())
+            },
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let mut poll = Poll::new(ctx, &self.address, self.timeout, self.verbose);
+            while let Some(max) = poll.next(self.try_connect_async().await)? {
+                let interval = self.poll_interval.calculate_wait_duration(ctx)?;
+                ctx.sleep_async(interval.min(max)).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Whether a [`ProcessExitWait`] ends when all or any of its processes have exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessExitMode {
+    All,
+    Any,
+}
+
+/// Waits until one or more processes exit. They need not be children of dozr.
+///
+/// On Linux each process is watched through a pidfd, so the wait ends as soon as it
+/// exits; `poll_interval` is then only a safety net. Elsewhere, for processes whose
+/// pidfd cannot be opened, and when waiting asynchronously, liveness is re-checked
+/// every `poll_interval`.
+pub struct ProcessExitWait {
+    pub pids: Vec<u32>,
+    pub mode: ProcessExitMode,
+    pub poll_interval: Duration,
+    pub timeout: Option<Duration>,
+    pub verbose: Option<Duration>,
+}
+
+impl ProcessExitWait {
+    fn is_satisfied(&self, watcher: &mut ProcessWatcher) -> Result<bool> {
+        let exited = watcher.exited()?;
+        Ok(match self.mode {
+            ProcessExitMode::All => exited == self.pids.len(),
+            ProcessExitMode::Any => exited > 0,
+        })
+    }
+
+    fn description(&self) -> String {
+        let pids: Vec<String> = self.pids.iter().map(u32::to_string).collect();
+        match (self.pids.len(), self.mode) {
+            (1, _) => format!("process {}", pids[0]),
+            (_, ProcessExitMode::All) => format!("all of processes {}", pids.join(", ")),
+            (_, ProcessExitMode::Any) => format!("any of processes {}", pids.join(", ")),
+        }
+    }
+}
+
+impl WaitCondition for ProcessExitWait {
+    /// The wait time is unknown up front: this is zero if the processes have
+    /// already exited, and otherwise the timeout (or [`Duration::MAX`] without one).
+    fn calculate_wait_duration(&self, _ctx: &WaitContext) -> Result<Duration> {
+        if self.is_satisfied(&mut ProcessWatcher::new(&self.pids))? {
+            Ok(Duration::ZERO)
+        } else {
+            Ok(self.timeout.unwrap_or(Duration::MAX))
+        }
+    }
+
+    fn is_timed(&self) -> bool {
+        false
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let watcher = RefCell::new(ProcessWatcher::new(&self.pids));
+        perform_poll(
+            ctx,
+            &self.description(),
+            self.timeout,
+            self.verbose,
+            || self.is_satisfied(&mut watcher.borrow_mut()),
+            |max| {
+                if ctx.clock().is_real_time() {
+                    watcher.borrow_mut().wait(self.poll_interval.min(max).min(CANCEL_CHECK_PERIOD))?;
+                } else {
+                    ctx.sleep(self.poll_interval.min(max))?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let description = self.description();
+            let mut watcher = ProcessWatcher::new(&self.pids);
+            let mut poll = Poll::new(ctx, &description, self.timeout, self.verbose);
+            while let Some(max) = poll.next(self.is_satisfied(&mut watcher)?)? {
+                ctx.sleep_async(self.poll_interval.min(max)).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Error returned when a retried command never succeeds.
+#[derive(Debug)]
+pub struct CommandFailedError {
+    pub attempts: u32,
+    pub status: ExitStatus,
+}
+
+impl CommandFailedError {
+    /// The exit code to propagate: the command's own code, or 128 + the signal
+    /// number if it was killed by a signal, as shells report it.
+    pub fn exit_code(&self) -> u8 {
+        if let Some(code) = self.status.code() {
+            return code as u8;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(signal) = self.status.signal() {
+                return 128u8.saturating_add(signal as u8);
+            }
+        }
+        1
+    }
+}
+
+impl fmt::Display for CommandFailedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.attempts == 1 { "" } else { "s" };
+        write!(f, "command failed after {} attempt{plural} ({})", self.attempts, self.status)
+    }
+}
+
+impl std::error::Error for CommandFailedError {}
+
+/// Runs a command until it succeeds, sleeping between attempts according to `backoff`.
+///
+/// Gives up after `max_attempts` runs, or once the next delay would take the total
+/// time past `max_elapsed`, returning a [`CommandFailedError`] with the last exit status.
+pub struct RetryWait {
+    pub command: Vec<OsString>,
+    pub backoff: Box<dyn Backoff>,
+    pub max_attempts: Option<u32>,
+    pub max_elapsed: Option<Duration>,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for RetryWait {
+    /// The wait time is unknown up front without running the command, so this
+    /// is `max_elapsed` (or [`Duration::MAX`] without one).
+    fn calculate_wait_duration(&self, _ctx: &WaitContext) -> Result<Duration> {
+        Ok(self.max_elapsed.unwrap_or(Duration::MAX))
+    }
+
+    fn is_timed(&self) -> bool {
+        false
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("no command given to retry"))?;
+        let start = ctx.clock().monotonic();
+        let mut attempt = 0;
+        let mut delay = Duration::ZERO;
+
+        loop {
+            ctx.check_cancelled()?;
+            attempt += 1;
+            let status = Command::new(program)
+                .args(args)
+                .status()
+                .with_context(|| format!("failed to run {}", program.to_string_lossy()))?;
+            if status.success() {
+                return Ok(());
+            }
+            delay = self.next_delay(ctx, start, attempt, status, delay)?;
+            perform_wait(ctx, delay, self.verbose)?;
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let (program, args) = self
+                .command
+                .split_first()
+                .ok_or_else(|| anyhow::anyhow!("no command given to retry"))?;
+            let start = ctx.clock().monotonic();
+            let mut attempt = 0;
+            let mut delay = Duration::ZERO;
+
+            loop {
+                attempt += 1;
+                let status = tokio::process::Command::new(program)
+                    .args(args)
+                    .kill_on_drop(true)
+                    .status()
+                    .await
+                    .with_context(|| format!("failed to run {}", program.to_string_lossy()))?;
+                if status.success() {
+                    return Ok(());
+                }
+                delay = self.next_delay(ctx, start, attempt, status, delay)?;
+                perform_wait_async(ctx, delay, self.verbose).await?;
+            }
+        })
+    }
+}
 
-        if should_sleep {
-            perform_wait(self.duration, self.verbose);
-        } else if self.verbose.is_some() {
+impl RetryWait {
+    /// Returns the delay before the next attempt after `attempt` failed with `status`,
+    /// or a [`CommandFailedError`] if it was the last one.
+    fn next_delay(
+        &self,
+        ctx: &WaitContext,
+        start: std::time::Instant,
+        attempt: u32,
+        status: ExitStatus,
+        previous: Duration,
+    ) -> Result<Duration> {
+        let failed = CommandFailedError { attempts: attempt, status };
+        if self.max_attempts.is_some_and(|max| attempt >= max) {
+            return Err(failed.into());
+        }
+        let delay = self.backoff.delay(ctx, attempt, previous)?;
+        if self.max_elapsed.is_some_and(|max| ctx.clock().monotonic().duration_since(start) + delay > max) {
+            return Err(failed.into());
+        }
+
+        if prints_text(ctx, self.verbose) {
             eprintln!(
-                "Probabilistic wait: Skipping sleep (probability: {}, roll: {:.2})",
-                self.probability, roll
+                "[{}] {} Attempt {} failed ({}); retrying in {}",
+                timestamp(ctx),
+                tag(ctx),
+                attempt,
+                status,
+                humantime::format_duration(round_to_millis(delay))
             );
         }
+        Ok(delay)
+    }
+}
+
+/// When the runs of a [`RepeatWait`] start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RepeatMode {
+    /// Wait the interval after each run finishes.
+    FixedDelay,
+    /// Wait the interval after each run starts, without waiting for it to finish.
+    FixedRate,
+}
+
+/// What a fixed-rate [`RepeatWait`] does when a run is due while earlier ones are still going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OverlapPolicy {
+    /// Skip the run and wait for the next one.
+    Skip,
+    /// Start the run as soon as the earlier ones finish.
+    Queue,
+    /// Start the run alongside the earlier ones.
+    Parallel,
+}
+
+/// Tallies the runs made by a [`RepeatWait`].
+#[derive(Debug, Default)]
+pub struct RepeatSummary {
+    pub elapsed: Duration,
+    pub runs: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub skipped: u64,
+}
+
+impl fmt::Display for RepeatSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.runs == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} run{plural} in {}: {} succeeded, {} failed, {} skipped",
+            self.runs,
+            humantime::format_duration(round_to_millis(self.elapsed)),
+            self.succeeded,
+            self.failed,
+            self.skipped
+        )
+    }
+}
+
+/// Runs a command repeatedly, waiting for `interval` before each run.
+///
+/// Runs forever unless `count` is given; failed runs are counted but do not stop the
+/// loop. If the context is cancelled, the [`RepeatSummary`] is printed and a
+/// [`CancelledError`] returned, whether waiting synchronously or not. When waiting
+/// asynchronously, dropping the future also stops the loop, killing any runs still going.
+pub struct RepeatWait {
+    pub command: Vec<OsString>,
+    pub interval: Box<dyn WaitCondition>,
+    pub count: Option<u64>,
+    pub mode: RepeatMode,
+    pub overlap: OverlapPolicy,
+    pub verbose: Option<Duration>,
+}
+
+impl RepeatWait {
+    fn record(&self, ctx: &WaitContext, summary: &Mutex<RepeatSummary>, status: ExitStatus) {
+        let mut summary = summary.lock().unwrap_or_else(|e| e.into_inner());
+        if status.success() {
+            summary.succeeded += 1;
+        } else {
+            summary.failed += 1;
+        }
+        if prints_text(ctx, self.verbose) {
+            eprintln!("[{}] {} Run finished ({})", timestamp(ctx), tag(ctx), status);
+        }
+    }
+
+    /// Records runs that have finished, first waiting for all of them if `block` is set.
+    fn reap(
+        &self,
+        ctx: &WaitContext,
+        running: &mut Vec<Child>,
+        summary: &Mutex<RepeatSummary>,
+        block: bool,
+    ) -> Result<()> {
+        let mut result = Ok(());
+        running.retain_mut(|child| {
+            let status = if block { child.wait().map(Some) } else { child.try_wait() };
+            match status {
+                Ok(Some(status)) => {
+                    self.record(ctx, summary, status);
+                    false
+                }
+                Ok(None) => true,
+                Err(err) => {
+                    result = Err(err);
+                    true
+                }
+            }
+        });
+        Ok(result?)
+    }
+
+    /// Like [`RepeatWait::reap`], for runs started by [`WaitCondition::wait_async`].
+    #[cfg(feature = "tokio")]
+    async fn reap_async(
+        &self,
+        ctx: &WaitContext,
+        running: &mut Vec<tokio::process::Child>,
+        summary: &Mutex<RepeatSummary>,
+        block: bool,
+    ) -> Result<()> {
+        if block {
+            for mut child in running.drain(..) {
+                let status = child.wait().await?;
+                self.record(ctx, summary, status);
+            }
+            return Ok(());
+        }
+        let mut result = Ok(());
+        running.retain_mut(|child| match child.try_wait() {
+            Ok(Some(status)) => {
+                self.record(ctx, summary, status);
+                false
+            }
+            Ok(None) => true,
+            Err(err) => {
+                result = Err(err);
+                true
+            }
+        });
+        Ok(result?)
+    }
+
+    /// Runs the command `count` times, or until cancelled.
+    fn run_all(&self, ctx: &WaitContext, summary: &Mutex<RepeatSummary>) -> Result<()> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("no command given to run"))?;
+        let spawn = || {
+            Command::new(program)
+                .args(args)
+                .spawn()
+                .with_context(|| format!("failed to run {}", program.to_string_lossy()))
+        };
+
+        let mut running = Vec::new();
+        let mut runs = 0;
+        while self.count.is_none_or(|count| runs < count) {
+            perform_wait(ctx, self.interval.calculate_wait_duration(ctx)?, self.verbose)?;
+            match self.mode {
+                RepeatMode::FixedDelay => {
+                    let status = spawn()?.wait()?;
+                    self.record(ctx, summary, status);
+                }
+                RepeatMode::FixedRate => {
+                    self.reap(ctx, &mut running, summary, false)?;
+                    if !running.is_empty() {
+                        match self.overlap {
+                            OverlapPolicy::Skip => {
+                                self.skip(ctx, summary);
+                                continue;
+                            }
+                            OverlapPolicy::Queue => self.reap(ctx, &mut running, summary, true)?,
+                            OverlapPolicy::Parallel => {}
+                        }
+                    }
+                    running.push(spawn()?);
+                }
+            }
+            runs += 1;
+            summary.lock().unwrap_or_else(|e| e.into_inner()).runs = runs;
+            ctx.check_cancelled()?;
+        }
+        self.reap(ctx, &mut running, summary, true)
+    }
+
+    /// Like [`RepeatWait::run_all`], without blocking the thread.
+    #[cfg(feature = "tokio")]
+    async fn run_all_async(&self, ctx: &WaitContext, summary: &Mutex<RepeatSummary>) -> Result<()> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("no command given to run"))?;
+        let spawn = || {
+            tokio::process::Command::new(program)
+                .args(args)
+                .kill_on_drop(true)
+                .spawn()
+                .with_context(|| format!("failed to run {}", program.to_string_lossy()))
+        };
+
+        let mut running = Vec::new();
+        let mut runs = 0;
+        while self.count.is_none_or(|count| runs < count) {
+            perform_wait_async(ctx, self.interval.calculate_wait_duration(ctx)?, self.verbose).await?;
+            match self.mode {
+                RepeatMode::FixedDelay => {
+                    let status = spawn()?.wait().await?;
+                    self.record(ctx, summary, status);
+                }
+                RepeatMode::FixedRate => {
+                    self.reap_async(ctx, &mut running, summary, false).await?;
+                    if !running.is_empty() {
+                        match self.overlap {
+                            OverlapPolicy::Skip => {
+                                self.skip(ctx, summary);
+                                continue;
+                            }
+                            OverlapPolicy::Queue => self.reap_async(ctx, &mut running, summary, true).await?,
+                            OverlapPolicy::Parallel => {}
+                        }
+                    }
+                    running.push(spawn()?);
+                }
+            }
+            runs += 1;
+            summary.lock().unwrap_or_else(|e| e.into_inner()).runs = runs;
+            ctx.check_cancelled()?;
+        }
+        self.reap_async(ctx, &mut running, summary, true).await
+    }
+
+    /// Prints the summary of the runs, as finished or interrupted, and passes `result` on.
+    fn conclude(
+        &self,
+        ctx: &WaitContext,
+        summary: &Mutex<RepeatSummary>,
+        started: std::time::Instant,
+        result: Result<()>,
+    ) -> Result<()> {
+        match &result {
+            Ok(()) => self.finish(ctx, summary, started),
+            Err(err) if err.is::<CancelledError>() => {
+                let mut summary = summary.lock().unwrap_or_else(|e| e.into_inner());
+                summary.elapsed = ctx.clock().monotonic().duration_since(started);
+                if ctx.json_events().is_none() {
+                    eprintln!("[{}] {} Interrupted after {}", timestamp(ctx), tag(ctx), summary);
+                }
+            }
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn skip(&self, ctx: &WaitContext, summary: &Mutex<RepeatSummary>) {
+        summary.lock().unwrap_or_else(|e| e.into_inner()).skipped += 1;
+        if prints_text(ctx, self.verbose) {
+            eprintln!("[{}] {} Skipping run: the previous one is still going", timestamp(ctx), tag(ctx));
+        }
+    }
+
+    fn finish(&self, ctx: &WaitContext, summary: &Mutex<RepeatSummary>, started: std::time::Instant) {
+        if prints_text(ctx, self.verbose) {
+            let mut summary = summary.lock().unwrap_or_else(|e| e.into_inner());
+            summary.elapsed = ctx.clock().monotonic().duration_since(started);
+            eprintln!("[{}] {} Finished {}", timestamp(ctx), tag(ctx), summary);
+        }
+    }
+}
+
+impl WaitCondition for RepeatWait {
+    fn calculate_wait_duration(&self, _ctx: &WaitContext) -> Result<Duration> {
+        Ok(Duration::MAX)
+    }
+
+    fn is_timed(&self) -> bool {
+        false
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let started = ctx.clock().monotonic();
+        let summary = Mutex::new(RepeatSummary::default());
+        let result = self.run_all(ctx, &summary);
+        self.conclude(ctx, &summary, started, result)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let started = ctx.clock().monotonic();
+            let summary = Mutex::new(RepeatSummary::default());
+            let result = self.run_all_async(ctx, &summary).await;
+            self.conclude(ctx, &summary, started, result)
+        })
+    }
+}
+
+/// Waits for each of `steps` in turn.
+///
+/// If every step is timed, their lengths are worked out up front, each for the time
+/// it will start, and waited for as one countdown. Otherwise each step runs on its own.
+pub struct Sequence {
+    pub steps: Vec<Box<dyn WaitCondition>>,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for Sequence {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        if !self.is_timed() {
+            return self.steps.iter().try_fold(Duration::ZERO, |total, step| {
+                Ok(total.saturating_add(step.calculate_wait_duration(ctx)?))
+            });
+        }
+        let start = ctx.clock().now();
+        let too_long = || anyhow::anyhow!("the sequence is too long to wait for");
+        let mut total = Duration::ZERO;
+        for step in &self.steps {
+            let step_start = ChronoDuration::from_std(total)
+                .ok()
+                .and_then(|delta| start.checked_add_signed(delta))
+                .ok_or_else(too_long)?;
+            let step_ctx = ctx.on_clock(Arc::new(VirtualClock::new(step_start)));
+            total = total.checked_add(step.calculate_wait_duration(&step_ctx)?).ok_or_else(too_long)?;
+        }
+        Ok(total)
+    }
+
+    fn is_timed(&self) -> bool {
+        self.steps.iter().all(|step| step.is_timed())
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        if self.is_timed() {
+            return perform_wait(ctx, self.calculate_wait_duration(ctx)?, self.verbose);
+        }
+        for step in &self.steps {
+            step.wait(ctx)?;
+        }
         Ok(())
     }
-}
 
-impl WaitCondition for DurationWait {
-    fn calculate_wait_duration(&self) -> Result<Duration> {
-        let mut rng = ThreadRng::default();
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        Ok(self.calculate_sleep_duration(&mut jitter_gen))
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            if self.is_timed() {
+                perform_wait_async(ctx, self.calculate_wait_duration(ctx)?, self.verbose).await?;
+                return Ok(());
+            }
+            for step in &self.steps {
+                step.wait_async(ctx).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Samples each of `waits` once, for a combinator to wait for as one countdown.
+///
+/// The waits must be timed; the CLI rejects waits for events when it parses them.
+fn sample_all(ctx: &WaitContext, waits: &[Box<dyn WaitCondition>], combinator: &str) -> Result<Vec<Duration>> {
+    debug_assert!(
+        waits.iter().all(|wait| wait.is_timed()),
+        "{combinator} only combines timed waits; use race for waits on events"
+    );
+    waits.iter().map(|wait| wait.calculate_wait_duration(ctx)).collect()
+}
+
+/// Waits for the longest of several timed waits.
+pub struct Max {
+    pub waits: Vec<Box<dyn WaitCondition>>,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for Max {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        Ok(sample_all(ctx, &self.waits, "max")?.into_iter().max().unwrap_or_default())
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Waits for the shortest of several timed waits.
+pub struct Min {
+    pub waits: Vec<Box<dyn WaitCondition>>,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for Min {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        Ok(sample_all(ctx, &self.waits, "min")?.into_iter().min().unwrap_or_default())
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Waits for the total of several timed waits, each worked out from now.
+pub struct Sum {
+    pub waits: Vec<Box<dyn WaitCondition>>,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for Sum {
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        Ok(sample_all(ctx, &self.waits, "sum")?.into_iter().fold(Duration::ZERO, Duration::saturating_add))
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        let sleep_duration = self.calculate_wait_duration(ctx)?;
+        perform_wait(ctx, sleep_duration, self.verbose)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move {
+            let sleep_duration = self.calculate_wait_duration(ctx)?;
+            perform_wait_async(ctx, sleep_duration, self.verbose).await?;
+            Ok(())
+        })
+    }
+}
+
+/// How often a [`Race`] passes controls and cancellation on to the waits in it.
+const RACE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Waits until the first of `waits` finishes, whether it succeeds or fails, then
+/// cancels the rest.
+///
+/// Each wait runs on its own thread, in a context with its own token and a
+/// generator seeded from the race's. Controls sent to the race go to every wait.
+pub struct Race {
+    pub waits: Vec<Box<dyn WaitCondition>>,
+    pub verbose: Option<Duration>,
+}
+
+impl WaitCondition for Race {
+    /// The shortest of the waits' durations, or of their bounds for waits on events.
+    fn calculate_wait_duration(&self, ctx: &WaitContext) -> Result<Duration> {
+        let durations = self.waits.iter().map(|wait| wait.calculate_wait_duration(ctx));
+        Ok(durations.collect::<Result<Vec<_>>>()?.into_iter().min().unwrap_or_default())
+    }
+
+    fn is_timed(&self) -> bool {
+        false
+    }
+
+    fn wait(&self, ctx: &WaitContext) -> Result<()> {
+        self.wait_outcome(ctx).map(|_| ())
+    }
+
+    fn wait_outcome(&self, ctx: &WaitContext) -> Result<WaitOutcome> {
+        let tokens: Vec<_> = self.waits.iter().map(|_| CancellationToken::new()).collect();
+        let contexts: Vec<_> = tokens.iter().map(|token| ctx.fork(token.clone())).collect();
+        let first = std::thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            for (index, (wait, wait_ctx)) in self.waits.iter().zip(&contexts).enumerate() {
+                let sender = sender.clone();
+                scope.spawn(move || sender.send((index, wait.wait_outcome(wait_ctx))));
+            }
+            drop(sender);
+            let first = loop {
+                match receiver.recv_timeout(RACE_POLL_INTERVAL) {
+                    Ok(first) => break Some(first),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break None,
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                }
+                for control in ctx.take_controls() {
+                    tokens.iter().for_each(|token| token.send(control));
+                }
+                if ctx.cancellation().is_cancelled() {
+                    tokens.iter().for_each(CancellationToken::cancel);
+                }
+            };
+            tokens.iter().for_each(CancellationToken::cancel);
+            first
+        });
+        let Some((winner, result)) = first else {
+            anyhow::bail!("a race needs at least one wait");
+        };
+        if result.is_ok() && prints_text(ctx, self.verbose) {
+            eprintln!("[{}] {} Race won by wait {}", timestamp(ctx), tag(ctx), winner + 1);
+        }
+        result
+    }
+
+    #[cfg(feature = "tokio")]
+    fn wait_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a> {
+        Box::pin(async move { self.wait_outcome_async(ctx).await.map(|_| ()) })
+    }
+
+    /// Polls every wait in turn; the ones still running are dropped, and so
+    /// cancelled, once one finishes.
+    #[cfg(feature = "tokio")]
+    fn wait_outcome_async<'a>(&'a self, ctx: &'a WaitContext) -> WaitFuture<'a, WaitOutcome> {
+        Box::pin(async move {
+            if self.waits.is_empty() {
+                anyhow::bail!("a race needs at least one wait");
+            }
+            let mut waits: Vec<_> = self.waits.iter().map(|wait| wait.wait_outcome_async(ctx)).collect();
+            std::future::poll_fn(|cx| {
+                waits
+                    .iter_mut()
+                    .find_map(|wait| match wait.as_mut().poll(cx) {
+                        std::task::Poll::Ready(result) => Some(result),
+                        std::task::Poll::Pending => None,
+                    })
+                    .map_or(std::task::Poll::Pending, std::task::Poll::Ready)
+            })
+            .await
+        })
+    }
+}
+
+fn round_to_millis(duration: Duration) -> Duration {
+    Duration::from_millis(duration.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Clock, VirtualClock};
+    use rand::SeedableRng;
+    use rand::rngs::ThreadRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// A context on a virtual clock starting at 2026-10-17T12:00:00Z, with a seeded generator.
+    fn virtual_ctx() -> (Arc<VirtualClock>, WaitContext) {
+        let start = DateTime::parse_from_rfc3339("2026-10-17T12:00:00Z").unwrap().to_utc();
+        let clock = Arc::new(VirtualClock::new(start));
+        let ctx = WaitContext::new(Arc::clone(&clock), ChaCha8Rng::seed_from_u64(7));
+        (clock, ctx)
+    }
+
+    // 4. Create a mock generator for testing.
+    struct MockJitterGenerator {
+        jitter: Duration,
+    }
+
+    impl JitterGenerator for MockJitterGenerator {
+        fn generate(&mut self, _max_jitter: Duration) -> Duration {
+            // Return the exact, predictable jitter for the test.
+            self.jitter
+        }
+    }
+
+    #[test]
+    fn test_duration_wait_creation() {
+        let duration = Duration::from_secs(1);
+        let wait_condition = DurationWait {
+            duration,
+            jitter: None,
+            verbose: None,
+        };
+        assert_eq!(wait_condition.duration, duration);
+    }
+
+    #[test]
+    fn test_calculate_sleep_duration_with_jitter() {
+        let mut mock_gen = MockJitterGenerator {
+            jitter: Duration::from_millis(1),
+        };
+        let wait_condition = DurationWait {
+            duration: Duration::from_secs(1),
+            jitter: Some(Duration::from_millis(500)),
+            verbose: None,
+        };
+
+        let calculated_duration = wait_condition.calculate_sleep_duration(&mut mock_gen);
+
+        // Assert that the base duration is correctly added to the mock jitter.
+        assert_eq!(calculated_duration, Duration::from_millis(1001));
+    }
+
+    #[test]
+    fn test_time_align_wait_calculation() {
+        // Test case 1: Current time is exactly on an alignment point
+        let now_secs = 100; // Example: 100 seconds past epoch
+        let align_interval = Duration::from_secs(10);
+        let expected_sleep = Duration::from_secs(10);
+        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
+        assert_eq!(calculated_sleep, expected_sleep);
+
+        // Test case 2: Current time is slightly past an alignment point
+        let now_secs = 103; // Example: 103 seconds past epoch
+        let align_interval = Duration::from_secs(10);
+        let expected_sleep = Duration::from_secs(7);
+        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
+        assert_eq!(calculated_sleep, expected_sleep);
+
+        // Test case 3: Current time is just before next alignment point
+        let now_secs = 109; // Example: 109 seconds past epoch
+        let align_interval = Duration::from_secs(10);
+        let expected_sleep = Duration::from_secs(1);
+        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
+        assert_eq!(calculated_sleep, expected_sleep);
+
+        // Test case 4: Alignment interval is 0
+        let now_secs = 100;
+        let align_interval = Duration::from_secs(0);
+        let expected_sleep = Duration::from_secs(0);
+        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
+        assert_eq!(calculated_sleep, expected_sleep);
+
+        // Test case 5: Larger alignment interval (e.g., 1 minute)
+        let now_secs = 65; // 1 minute and 5 seconds past epoch
+        let align_interval = Duration::from_secs(60);
+        let expected_sleep = Duration::from_secs(55); // Should align to 2 minutes mark
+        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
+        assert_eq!(calculated_sleep, expected_sleep);
+    }
+
+    // Helper function for testing TimeAlignWait
+    fn calculate_time_to_next_alignment(now_secs: u64, align_interval: Duration) -> Duration {
+        if align_interval.as_secs() == 0 {
+            return Duration::ZERO;
+        }
+        let remainder = now_secs % align_interval.as_secs();
+        if remainder == 0 {
+            align_interval
+        } else {
+            align_interval - Duration::from_secs(remainder)
+        }
+    }
+
+    #[test]
+    fn test_probabilistic_wait_always_sleeps_at_1_0_probability() {
+        let wait_condition = ProbabilisticWait {
+            inner: Box::new(DurationWait { duration: Duration::from_millis(100), verbose: None, jitter: None }),
+            probability: 1.0,
+            verbose: None,
+        };
+        let (clock, ctx) = virtual_ctx();
+        wait_condition.wait(&ctx).unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_probabilistic_wait_never_sleeps_at_0_0_probability() {
+        let wait_condition = ProbabilisticWait {
+            inner: Box::new(DurationWait { duration: Duration::from_millis(100), verbose: None, jitter: None }),
+            probability: 0.0,
+            verbose: None,
+        };
+        let (clock, ctx) = virtual_ctx();
+        wait_condition.wait(&ctx).unwrap();
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_probabilistic_wait_wraps_any_condition() {
+        let wait_condition = ProbabilisticWait {
+            inner: Box::new(NormalWait {
+                mean: Duration::from_secs(10),
+                std_dev: 1.0,
+                verbose: None,
+                jitter: Some(Duration::from_secs(5)),
+            }),
+            probability: 1.0,
+            verbose: None,
+        };
+        // The virtual context's generator is seeded with 7 too.
+        let expected = wait_condition.calculate_wait_duration(&WaitContext::seeded(7)).unwrap();
+        let (clock, ctx) = virtual_ctx();
+        wait_condition.wait(&ctx).unwrap();
+        assert_eq!(clock.elapsed(), expected);
+        assert!(expected > Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_probabilistic_wait_reports_outcome() {
+        let mut wait_condition = ProbabilisticWait {
+            inner: Box::new(DurationWait { duration: Duration::from_millis(10), verbose: None, jitter: None }),
+            probability: 0.0,
+            verbose: None,
+        };
+        let (_, ctx) = virtual_ctx();
+        assert_eq!(wait_condition.wait_outcome(&ctx).unwrap(), WaitOutcome::Skipped);
+        wait_condition.probability = 1.0;
+        assert_eq!(wait_condition.wait_outcome(&ctx).unwrap(), WaitOutcome::Completed);
+    }
+    #[test]
+    fn test_jitter_generator_non_zero_max_jitter() {
+        let mut rng = ThreadRng::default();
+        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
+        let max_jitter = Duration::from_millis(100);
+        let generated_jitter = jitter_gen.generate(max_jitter);
+        assert!(generated_jitter <= max_jitter);
+    }
+
+    #[test]
+    fn test_jitter_generator_zero_max_jitter() {
+        let mut rng = ThreadRng::default();
+        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
+        let max_jitter = Duration::ZERO;
+        let generated_jitter = jitter_gen.generate(max_jitter);
+        assert_eq!(generated_jitter, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_normal_wait_calculate_duration() {
+        let wait = NormalWait {
+            mean: Duration::from_secs(1),
+            std_dev: 0.1,
+            verbose: None,
+            jitter: None,
+        };
+        // We can't predict the exact value, but we can check if it's reasonable
+        // and doesn't panic. A simple check is that it's not negative.
+        let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+        assert!(duration >= Duration::ZERO);
+    }
+
+    #[test]
+    fn test_exponential_wait_calculate_duration() {
+        let wait = ExponentialWait {
+            lambda: 1.0,
+            verbose: None,
+            jitter: None,
+        };
+        let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+        assert!(duration >= Duration::ZERO);
+    }
+
+    #[test]
+    fn test_log_normal_wait_calculate_duration() {
+        let wait = LogNormalWait {
+            params: LogNormalParams::Log { mu: 1.0, sigma: 0.1 },
+            verbose: None,
+            jitter: None,
+        };
+        let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+        assert!(duration >= Duration::ZERO);
+    }
+
+    #[test]
+    fn test_log_normal_params() {
+        let solve = |params: LogNormalParams| {
+            let (mu, sigma) = params.solve().unwrap();
+            ((mu * 1e6).round() / 1e6, (sigma * 1e6).round() / 1e6)
+        };
+        assert_eq!(solve(LogNormalParams::Log { mu: 1.0, sigma: 0.5 }), (1.0, 0.5));
+        // sigma = sqrt(ln(1 + cv^2)), and the median is e^mu.
+        assert_eq!(solve(LogNormalParams::Median { median: Duration::from_secs(1), cv: 0.5 }), (0.0, 0.472381));
+        // The mean is e^(mu + sigma^2 / 2).
+        assert_eq!(solve(LogNormalParams::Mean { mean: Duration::from_secs(1), cv: 0.5 }), (-0.111572, 0.472381));
+        // The 99th percentile is e^(mu + 2.326 sigma).
+        let p50 = Duration::from_secs(1);
+        let p99 = Duration::from_secs(10);
+        assert_eq!(solve(LogNormalParams::Percentiles { p50, p99 }), (0.0, 0.989785));
+
+        let describe = |params| LogNormalWait { params, verbose: None, jitter: None }.describe().unwrap();
+        assert_eq!(
+            describe(LogNormalParams::Percentiles { p50, p99 }),
+            "Log-normal with mu 0.000 and sigma 0.990: median 1s, mean 1s 632ms, 99th percentile 10s"
+        );
+
+        let err = LogNormalParams::Percentiles { p50: p99, p99: p50 }.solve().unwrap_err();
+        assert_eq!(err.to_string(), "the 99th percentile (1s) must not be below the median (10s)");
+        let err = LogNormalParams::Mean { mean: Duration::ZERO, cv: 0.5 }.solve().unwrap_err();
+        assert_eq!(err.to_string(), "the mean of a log-normal distribution must be above zero");
+        let err = LogNormalParams::Median { median: p50, cv: -1.0 }.solve().unwrap_err();
+        assert_eq!(err.to_string(), "the coefficient of variation must be a number from 0 up: -1");
+    }
+
+    #[test]
+    fn test_log_normal_wait_samples_around_its_median() {
+        let ctx = WaitContext::seeded(11);
+        let wait = LogNormalWait {
+            params: LogNormalParams::Median { median: Duration::from_secs(2), cv: 0.5 },
+            verbose: None,
+            jitter: None,
+        };
+        let below = (0..1000)
+            .filter(|_| wait.calculate_wait_duration(&ctx).unwrap() < Duration::from_secs(2))
+            .count();
+        assert!((450..550).contains(&below), "{below}");
+    }
+
+    #[test]
+    fn test_pareto_wait_calculate_duration() {
+        let wait = ParetoWait {
+            scale: 1.0,
+            shape: 1.0,
+            verbose: None,
+            jitter: None,
+        };
+        let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+        assert!(duration >= Duration::ZERO);
+    }
+
+    #[test]
+    fn test_uniform_wait_calculate_duration() {
+        let wait = UniformWait {
+            min: Duration::from_secs(1),
+            max: Duration::from_secs(2),
+            verbose: None,
+            jitter: None,
+        };
+        let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+        assert!(duration >= Duration::from_secs(1) && duration <= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_triangular_wait_calculate_duration() {
+        let wait = TriangularWait {
+            min: 1.0,
+            max: 3.0,
+            mode: 2.0,
+            verbose: None,
+            jitter: None,
+        };
+        let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+        assert!(duration >= Duration::from_secs_f64(1.0) && duration <= Duration::from_secs_f64(3.0));
+    }
+
+    #[test]
+    fn test_gamma_wait_calculate_duration() {
+        let wait = GammaWait {
+            shape: 2.0,
+            scale: 1.0,
+            verbose: None,
+            jitter: None,
+        };
+        let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+        assert!(duration >= Duration::ZERO);
+    }
+
+    #[test]
+    fn test_file_exists_wait_returns_when_path_appears() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("marker");
+        let wait = FileExistsWait {
+            path: path.clone(),
+            poll_interval: Duration::from_secs(5),
+            timeout: Some(Duration::from_secs(10)),
+            verbose: None,
+        };
+
+        let creator = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            std::fs::create_dir(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"").unwrap();
+        });
+        let start_time = Instant::now();
+        wait.wait(&WaitContext::system()).unwrap();
+        creator.join().unwrap();
+
+        // Well under the poll interval when inotify is available.
+        #[cfg(target_os = "linux")]
+        assert!(start_time.elapsed() < Duration::from_secs(2));
+        assert!(wait.path.exists());
+    }
+
+    #[test]
+    fn test_file_exists_wait_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let wait = FileExistsWait {
+            path: dir.path().join("never"),
+            poll_interval: Duration::from_millis(10),
+            timeout: Some(Duration::from_millis(100)),
+            verbose: None,
+        };
+        let (clock, ctx) = virtual_ctx();
+        let err = wait.wait(&ctx).unwrap_err();
+        assert!(err.is::<TimeoutError>());
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_file_exists_wait_calculate_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        let wait = FileExistsWait {
+            path: dir.path().to_path_buf(),
+            poll_interval: Duration::from_secs(1),
+            timeout: None,
+            verbose: None,
+        };
+        assert_eq!(wait.calculate_wait_duration(&WaitContext::system()).unwrap(), Duration::ZERO);
+    }
+
+    fn fixed_interval(interval: Duration) -> Box<dyn WaitCondition> {
+        Box::new(DurationWait {
+            duration: interval,
+            verbose: None,
+            jitter: None,
+        })
+    }
+
+    #[test]
+    fn test_port_wait_succeeds_when_listening() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let wait = PortWait {
+            address: listener.local_addr().unwrap().to_string(),
+            poll_interval: fixed_interval(Duration::from_millis(10)),
+            connect_timeout: Duration::from_secs(1),
+            timeout: Some(Duration::from_secs(5)),
+            verbose: None,
+        };
+        let ctx = WaitContext::system();
+        assert_eq!(wait.calculate_wait_duration(&ctx).unwrap(), Duration::ZERO);
+        wait.wait(&ctx).unwrap();
+    }
+
+    #[test]
+    fn test_port_wait_waits_for_listener() {
+        // Reserve a free port, then release it until the listener thread starts.
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let wait = PortWait {
+            address: address.to_string(),
+            poll_interval: fixed_interval(Duration::from_millis(20)),
+            connect_timeout: Duration::from_millis(100),
+            timeout: Some(Duration::from_secs(5)),
+            verbose: None,
+        };
+        let server = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            let listener = std::net::TcpListener::bind(address).unwrap();
+            listener.accept().unwrap();
+        });
+        let start_time = Instant::now();
+        wait.wait(&WaitContext::system()).unwrap();
+        assert!(start_time.elapsed() >= Duration::from_millis(150));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_port_wait_times_out() {
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let wait = PortWait {
+            address: address.to_string(),
+            poll_interval: Box::new(ExponentialWait {
+                lambda: 50.0,
+                verbose: None,
+                jitter: None,
+            }),
+            connect_timeout: Duration::from_millis(100),
+            timeout: Some(Duration::from_millis(200)),
+            verbose: None,
+        };
+        let err = wait.wait(&WaitContext::system()).unwrap_err();
+        assert!(err.is::<TimeoutError>());
+    }
+
+    #[cfg(unix)]
+    fn spawn_sleep(duration: &str) -> (u32, std::thread::JoinHandle<()>) {
+        let mut child = std::process::Command::new("sleep").arg(duration).spawn().unwrap();
+        let pid = child.id();
+        // Reap the child in the background so it doesn't linger as a zombie.
+        (pid, std::thread::spawn(move || { child.wait().unwrap(); }))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_exit_wait_all() {
+        let (short_pid, short) = spawn_sleep("0.1");
+        let (long_pid, long) = spawn_sleep("0.4");
+        let wait = ProcessExitWait {
+            pids: vec![short_pid, long_pid],
+            mode: ProcessExitMode::All,
+            poll_interval: Duration::from_millis(50),
+            timeout: Some(Duration::from_secs(5)),
+            verbose: None,
+        };
+        let start_time = Instant::now();
+        wait.wait(&WaitContext::system()).unwrap();
+        assert!(start_time.elapsed() >= Duration::from_millis(350));
+        short.join().unwrap();
+        long.join().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_exit_wait_any() {
+        let (short_pid, short) = spawn_sleep("0.1");
+        let (long_pid, long) = spawn_sleep("1");
+        let wait = ProcessExitWait {
+            pids: vec![short_pid, long_pid],
+            mode: ProcessExitMode::Any,
+            poll_interval: Duration::from_millis(50),
+            timeout: Some(Duration::from_secs(5)),
+            verbose: None,
+        };
+        let start_time = Instant::now();
+        wait.wait(&WaitContext::system()).unwrap();
+        assert!(start_time.elapsed() < Duration::from_millis(800));
+        short.join().unwrap();
+        long.join().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_exit_wait_times_out() {
+        let (pid, child) = spawn_sleep("1");
+        let wait = ProcessExitWait {
+            pids: vec![pid],
+            mode: ProcessExitMode::All,
+            poll_interval: Duration::from_millis(50),
+            timeout: Some(Duration::from_millis(100)),
+            verbose: None,
+        };
+        let (clock, ctx) = virtual_ctx();
+        assert!(wait.wait(&ctx).unwrap_err().is::<TimeoutError>());
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+        assert_eq!(wait.calculate_wait_duration(&ctx).unwrap(), Duration::from_millis(100));
+        child.join().unwrap();
+        assert_eq!(wait.calculate_wait_duration(&ctx).unwrap(), Duration::ZERO);
     }
 
-    fn wait(&self) -> Result<()> {
-        let sleep_duration = self.calculate_wait_duration()?;
-        perform_wait(sleep_duration, self.verbose);
-        Ok(())
+    #[test]
+    fn test_strategy_backoff_delays() {
+        let mut no_jitter = MockJitterGenerator { jitter: Duration::ZERO };
+        let backoff = |strategy| StrategyBackoff {
+            strategy,
+            base: Duration::from_millis(100),
+            cap: Some(Duration::from_millis(500)),
+            jitter: None,
+        };
+
+        let constant = backoff(BackoffStrategy::Constant);
+        assert_eq!(constant.calculate_delay(1, Duration::ZERO, &mut no_jitter), Duration::from_millis(100));
+        assert_eq!(constant.calculate_delay(4, Duration::ZERO, &mut no_jitter), Duration::from_millis(100));
+
+        let linear = backoff(BackoffStrategy::Linear);
+        assert_eq!(linear.calculate_delay(1, Duration::ZERO, &mut no_jitter), Duration::from_millis(100));
+        assert_eq!(linear.calculate_delay(3, Duration::ZERO, &mut no_jitter), Duration::from_millis(300));
+        assert_eq!(linear.calculate_delay(9, Duration::ZERO, &mut no_jitter), Duration::from_millis(500));
+
+        let exponential = backoff(BackoffStrategy::Exponential);
+        assert_eq!(exponential.calculate_delay(1, Duration::ZERO, &mut no_jitter), Duration::from_millis(100));
+        assert_eq!(exponential.calculate_delay(3, Duration::ZERO, &mut no_jitter), Duration::from_millis(400));
+        assert_eq!(exponential.calculate_delay(4, Duration::ZERO, &mut no_jitter), Duration::from_millis(500));
+        // Huge attempt counts saturate rather than overflow.
+        assert_eq!(exponential.calculate_delay(200, Duration::ZERO, &mut no_jitter), Duration::from_millis(500));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::{Duration, Instant};
+    #[test]
+    fn test_strategy_backoff_adds_jitter() {
+        let mut mock_gen = MockJitterGenerator { jitter: Duration::from_millis(7) };
+        let backoff = StrategyBackoff {
+            strategy: BackoffStrategy::Constant,
+            base: Duration::from_millis(100),
+            cap: None,
+            jitter: Some(Duration::from_millis(10)),
+        };
+        assert_eq!(backoff.calculate_delay(1, Duration::ZERO, &mut mock_gen), Duration::from_millis(107));
+    }
 
-    // 4. Create a mock generator for testing.
-    struct MockJitterGenerator {
-        jitter: Duration,
+    // Returns a fixed fraction of the maximum, to exercise the jittered strategies.
+    struct FractionJitterGenerator {
+        fraction: f64,
     }
 
-    impl JitterGenerator for MockJitterGenerator {
-        fn generate(&mut self, _max_jitter: Duration) -> Duration {
-            // Return the exact, predictable jitter for the test.
-            self.jitter
+    impl JitterGenerator for FractionJitterGenerator {
+        fn generate(&mut self, max_jitter: Duration) -> Duration {
+            max_jitter.mul_f64(self.fraction)
         }
     }
 
     #[test]
-    fn test_duration_wait_creation() {
-        let duration = Duration::from_secs(1);
-        let wait_condition = DurationWait {
-            duration,
+    fn test_strategy_backoff_aws_jitter() {
+        let backoff = |strategy| StrategyBackoff {
+            strategy,
+            base: Duration::from_millis(100),
+            cap: Some(Duration::from_secs(1)),
             jitter: None,
-            verbose: None,
         };
-        assert_eq!(wait_condition.duration, duration);
+        let mut half = FractionJitterGenerator { fraction: 0.5 };
+
+        // Full jitter: uniform over [0, min(cap, base * 2^(attempt - 1))].
+        let full = backoff(BackoffStrategy::FullJitter);
+        assert_eq!(full.calculate_delay(3, Duration::ZERO, &mut half), Duration::from_millis(200));
+        assert_eq!(full.calculate_delay(10, Duration::ZERO, &mut half), Duration::from_millis(500));
+
+        // Equal jitter: half of the exponential delay plus up to the other half.
+        let equal = backoff(BackoffStrategy::EqualJitter);
+        assert_eq!(equal.calculate_delay(3, Duration::ZERO, &mut half), Duration::from_millis(300));
+        assert_eq!(equal.calculate_delay(10, Duration::ZERO, &mut half), Duration::from_millis(750));
+
+        // Decorrelated jitter: uniform over [base, previous * 3], capped.
+        let decorrelated = backoff(BackoffStrategy::DecorrelatedJitter);
+        assert_eq!(decorrelated.calculate_delay(1, Duration::ZERO, &mut half), Duration::from_millis(200));
+        assert_eq!(
+            decorrelated.calculate_delay(2, Duration::from_millis(200), &mut half),
+            Duration::from_millis(350)
+        );
+        assert_eq!(
+            decorrelated.calculate_delay(5, Duration::from_secs(1), &mut half),
+            Duration::from_secs(1)
+        );
     }
 
     #[test]
-    fn test_calculate_sleep_duration_with_jitter() {
-        let mut mock_gen = MockJitterGenerator {
-            jitter: Duration::from_millis(1),
-        };
-        let wait_condition = DurationWait {
-            duration: Duration::from_secs(1),
-            jitter: Some(Duration::from_millis(500)),
+    fn test_backoff_wait_stays_within_bounds() {
+        for strategy in [BackoffStrategy::FullJitter, BackoffStrategy::EqualJitter, BackoffStrategy::DecorrelatedJitter] {
+            let wait = BackoffWait {
+                backoff: StrategyBackoff {
+                    strategy,
+                    base: Duration::from_millis(100),
+                    cap: Some(Duration::from_secs(2)),
+                    jitter: None,
+                },
+                attempt: 6,
+                verbose: None,
+            };
+            let duration = wait.calculate_wait_duration(&WaitContext::system()).unwrap();
+            assert!(duration <= Duration::from_secs(2), "{strategy:?} gave {duration:?}");
+        }
+    }
+
+    #[cfg(unix)]
+    fn retry_shell(script: &str, max_attempts: Option<u32>, max_elapsed: Option<Duration>) -> RetryWait {
+        RetryWait {
+            command: vec!["sh".into(), "-c".into(), script.into()],
+            backoff: Box::new(StrategyBackoff {
+                strategy: BackoffStrategy::Constant,
+                base: Duration::from_millis(10),
+                cap: None,
+                jitter: None,
+            }),
+            max_attempts,
+            max_elapsed,
             verbose: None,
-        };
+        }
+    }
 
-        let calculated_duration = wait_condition.calculate_sleep_duration(&mut mock_gen);
+    #[cfg(unix)]
+    #[test]
+    fn test_retry_wait_propagates_final_exit_code() {
+        let (clock, ctx) = virtual_ctx();
+        let err = retry_shell("exit 3", Some(3), None).wait(&ctx).unwrap_err();
+        let failed = err.downcast_ref::<CommandFailedError>().unwrap();
+        assert_eq!(failed.attempts, 3);
+        assert_eq!(failed.exit_code(), 3);
+        assert_eq!(clock.elapsed(), Duration::from_millis(20));
+    }
 
-        // Assert that the base duration is correctly added to the mock jitter.
-        assert_eq!(calculated_duration, Duration::from_millis(1001));
+    #[cfg(unix)]
+    #[test]
+    fn test_retry_wait_stops_at_max_elapsed() {
+        let (clock, ctx) = virtual_ctx();
+        let err = retry_shell("exit 1", None, Some(Duration::from_millis(100))).wait(&ctx).unwrap_err();
+        assert_eq!(err.downcast_ref::<CommandFailedError>().unwrap().attempts, 11);
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_time_align_wait_calculation() {
-        // Test case 1: Current time is exactly on an alignment point
-        let now_secs = 100; // Example: 100 seconds past epoch
-        let align_interval = Duration::from_secs(10);
-        let expected_sleep = Duration::from_secs(10);
-        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
-        assert_eq!(calculated_sleep, expected_sleep);
+    fn test_retry_wait_succeeds_after_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("attempted");
+        let script = format!("test -f {0} || {{ touch {0}; exit 1; }}", marker.display());
+        let (clock, ctx) = virtual_ctx();
+        retry_shell(&script, Some(3), None).wait(&ctx).unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_millis(10));
+    }
 
-        // Test case 2: Current time is slightly past an alignment point
-        let now_secs = 103; // Example: 103 seconds past epoch
-        let align_interval = Duration::from_secs(10);
-        let expected_sleep = Duration::from_secs(7);
-        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
-        assert_eq!(calculated_sleep, expected_sleep);
+    #[test]
+    fn test_condition_backoff_samples_condition() {
+        let backoff = ConditionBackoff(Box::new(UniformWait {
+            min: Duration::from_millis(10),
+            max: Duration::from_millis(20),
+            verbose: None,
+            jitter: None,
+        }));
+        let (_, ctx) = virtual_ctx();
+        let delay = backoff.delay(&ctx, 1, Duration::ZERO).unwrap();
+        assert!(delay >= Duration::from_millis(10) && delay <= Duration::from_millis(20));
+    }
 
-        // Test case 3: Current time is just before next alignment point
-        let now_secs = 109; // Example: 109 seconds past epoch
-        let align_interval = Duration::from_secs(10);
-        let expected_sleep = Duration::from_secs(1);
-        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
-        assert_eq!(calculated_sleep, expected_sleep);
+    #[test]
+    fn test_cron_wait_next_run_in_time_zone() {
+        let wait = CronWait {
+            schedule: "0 9 * * *".parse().unwrap(),
+            tz: Some(chrono_tz::Europe::London),
+            verbose: None,
+        };
+        let now = DateTime::parse_from_rfc3339("2026-10-17T12:00:00Z").unwrap().with_timezone(&Utc);
+        // 09:00 in London during BST is 08:00 UTC.
+        assert_eq!(wait.next_run(now).unwrap().to_rfc3339(), "2026-10-18T09:00:00+01:00");
+    }
 
-        // Test case 4: Alignment interval is 0
-        let now_secs = 100;
-        let align_interval = Duration::from_secs(0);
-        let expected_sleep = Duration::from_secs(0);
-        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
-        assert_eq!(calculated_sleep, expected_sleep);
+    #[test]
+    fn test_cron_wait_calculate_duration() {
+        let wait = CronWait {
+            schedule: "* * * * * *".parse().unwrap(),
+            tz: None,
+            verbose: None,
+        };
+        let (_, ctx) = virtual_ctx();
+        assert_eq!(wait.calculate_wait_duration(&ctx).unwrap(), Duration::from_secs(1));
+    }
 
-        // Test case 5: Larger alignment interval (e.g., 1 minute)
-        let now_secs = 65; // 1 minute and 5 seconds past epoch
-        let align_interval = Duration::from_secs(60);
-        let expected_sleep = Duration::from_secs(55); // Should align to 2 minutes mark
-        let calculated_sleep = calculate_time_to_next_alignment(now_secs, align_interval);
-        assert_eq!(calculated_sleep, expected_sleep);
+    fn new_york(s: &str) -> DateTime<Tz> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&chrono_tz::America::New_York)
     }
 
-    // Helper function for testing TimeAlignWait
-    fn calculate_time_to_next_alignment(now_secs: u64, align_interval: Duration) -> Duration {
-        if align_interval.as_secs() == 0 {
-            return Duration::ZERO;
-        }
-        let remainder = now_secs % align_interval.as_secs();
-        if remainder == 0 {
-            align_interval
-        } else {
-            align_interval - Duration::from_secs(remainder)
-        }
+    fn align_to(alignment: Alignment, offset: Duration, now: &DateTime<Tz>) -> String {
+        let wait = TimeAlignWait { alignment, offset, tz: None, verbose: None };
+        wait.next_boundary(now).unwrap().to_rfc3339()
+    }
+
+    fn align(interval: Duration, now: &DateTime<Tz>) -> String {
+        align_to(Alignment::Interval(interval), Duration::ZERO, now)
     }
 
     #[test]
-    fn test_probabilistic_wait_always_sleeps_at_1_0_probability() {
-        let wait_condition = ProbabilisticWait {
-            duration: Duration::from_millis(100),
-            probability: 1.0,
-            verbose: None,
-        };
-        let start_time = Instant::now();
-        wait_condition.wait().unwrap();
-        let elapsed = start_time.elapsed();
-        assert!(elapsed >= Duration::from_millis(100));
+    fn test_time_align_on_zone_wall_clock() {
+        // India is at +05:30, so whole local hours fall on the half hour in UTC.
+        let now = DateTime::parse_from_rfc3339("2026-10-17T10:10:00Z").unwrap().with_timezone(&chrono_tz::Asia::Kolkata);
+        assert_eq!(align(Duration::from_secs(3600), &now), "2026-10-17T16:00:00+05:30");
+        assert_eq!(align(Duration::from_secs(86400), &new_york("2026-10-17T10:10:00Z")), "2026-10-18T00:00:00-04:00");
     }
 
     #[test]
-    fn test_probabilistic_wait_never_sleeps_at_0_0_probability() {
-        let wait_condition = ProbabilisticWait {
-            duration: Duration::from_millis(100),
-            probability: 0.0,
+    fn test_time_align_across_dst_transitions() {
+        // 02:00 doesn't exist on 2026-03-08 in New York; the boundary moves forward to 03:00 EDT.
+        assert_eq!(align(Duration::from_secs(3600), &new_york("2026-03-08T06:30:00Z")), "2026-03-08T03:00:00-04:00");
+        // 01:30 happens twice on 2026-11-01. During the second 01:xx hour, the next
+        // half hour is the second 01:30, not the already-passed first one.
+        assert_eq!(align(Duration::from_secs(1800), &new_york("2026-11-01T05:10:00Z")), "2026-11-01T01:30:00-04:00");
+        assert_eq!(align(Duration::from_secs(1800), &new_york("2026-11-01T06:10:00Z")), "2026-11-01T01:30:00-05:00");
+    }
+
+    #[test]
+    fn test_time_target_resolves_dst_gaps_and_overlaps() {
+        let at = |target: TimeTarget, now: &str| target.resolve(&new_york(now)).to_rfc3339();
+        let naive = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        // Skipped by the spring-forward gap: moves forward by an hour.
+        assert_eq!(
+            at(TimeTarget::LocalDateTime(naive("2026-03-08 02:30")), "2026-03-01T00:00:00Z"),
+            "2026-03-08T03:30:00-04:00"
+        );
+        // Repeated by the fall-back overlap: the first occurrence.
+        assert_eq!(
+            at(TimeTarget::LocalDateTime(naive("2026-11-01 01:30")), "2026-10-01T00:00:00Z"),
+            "2026-11-01T01:30:00-04:00"
+        );
+        // Times of day are on the zone's clock, rolling over to tomorrow once passed.
+        let nine = TimeTarget::TimeOfDay(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(at(nine, "2026-10-17T12:00:00Z"), "2026-10-17T09:00:00-04:00");
+        assert_eq!(at(nine, "2026-10-17T14:00:00Z"), "2026-10-18T09:00:00-04:00");
+    }
+
+    #[test]
+    fn test_until_time_wait_in_time_zone() {
+        let wait = UntilTimeWait {
+            target: TimeTarget::Date(NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()),
+            allow_past: false,
+            tz: Some(chrono_tz::Europe::London),
             verbose: None,
         };
-        let start_time = Instant::now();
-        wait_condition.wait().unwrap();
-        let elapsed = start_time.elapsed();
-        assert!(elapsed < Duration::from_millis(50)); // Should be very fast, not actually sleep
+        let now = DateTime::parse_from_rfc3339("2026-10-17T22:00:00Z").unwrap().with_timezone(&chrono_tz::Europe::London);
+        // Midnight in London during BST is 23:00 UTC.
+        assert_eq!(wait.duration_from(&now).unwrap(), Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_time_align_interval_with_offset() {
+        let hour = Alignment::Interval(Duration::from_secs(3600));
+        let five_minutes = Duration::from_secs(300);
+        assert_eq!(align_to(hour, five_minutes, &new_york("2026-10-17T10:03:00Z")), "2026-10-17T06:05:00-04:00");
+        assert_eq!(align_to(hour, five_minutes, &new_york("2026-10-17T10:05:00Z")), "2026-10-17T07:05:00-04:00");
+        assert_eq!(align_to(hour, five_minutes, &new_york("2026-10-17T10:06:00Z")), "2026-10-17T07:05:00-04:00");
     }
+
     #[test]
-    fn test_jitter_generator_non_zero_max_jitter() {
-        let mut rng = ThreadRng::default();
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let max_jitter = Duration::from_millis(100);
-        let generated_jitter = jitter_gen.generate(max_jitter);
-        assert!(generated_jitter <= max_jitter);
+    fn test_time_align_to_calendar_units() {
+        // 2026-10-17 is a Saturday.
+        let now = new_york("2026-10-17T14:00:00Z");
+        let day = Alignment::Calendar(CalendarUnit::Day);
+        assert_eq!(align_to(day, Duration::ZERO, &now), "2026-10-18T00:00:00-04:00");
+        assert_eq!(align_to(day, Duration::from_secs(9000), &now), "2026-10-18T02:30:00-04:00");
+        assert_eq!(align_to(day, Duration::from_secs(11 * 3600), &now), "2026-10-17T11:00:00-04:00");
+        assert_eq!(align_to(Alignment::Calendar(CalendarUnit::Week), Duration::ZERO, &now), "2026-10-19T00:00:00-04:00");
+        assert_eq!(align_to(Alignment::Calendar(CalendarUnit::Month), Duration::ZERO, &now), "2026-11-01T00:00:00-04:00");
+        // The first of December is on standard time.
+        let december = new_york("2026-11-15T12:00:00Z");
+        assert_eq!(align_to(Alignment::Calendar(CalendarUnit::Month), Duration::ZERO, &december), "2026-12-01T00:00:00-05:00");
     }
 
     #[test]
-    fn test_jitter_generator_zero_max_jitter() {
-        let mut rng = ThreadRng::default();
-        let mut jitter_gen = RandomJitterGenerator::new(&mut rng);
-        let max_jitter = Duration::ZERO;
-        let generated_jitter = jitter_gen.generate(max_jitter);
-        assert_eq!(generated_jitter, Duration::ZERO);
+    fn test_time_align_to_day_across_dst_gap() {
+        // 02:30 doesn't exist on 2026-03-08 in New York, so that day's boundary is 03:30 EDT.
+        let day = Alignment::Calendar(CalendarUnit::Day);
+        let offset = Duration::from_secs(9000);
+        assert_eq!(align_to(day, offset, &new_york("2026-03-08T05:00:00Z")), "2026-03-08T03:30:00-04:00");
+        assert_eq!(align_to(day, offset, &new_york("2026-03-08T08:00:00Z")), "2026-03-09T02:30:00-04:00");
     }
 
     #[test]
-    fn test_normal_wait_calculate_duration() {
-        let wait = NormalWait {
-            mean: Duration::from_secs(1),
-            std_dev: 0.1,
+    fn test_repeat_summary_display() {
+        let summary = RepeatSummary {
+            elapsed: Duration::from_millis(1500),
+            runs: 3,
+            succeeded: 2,
+            failed: 1,
+            skipped: 4,
+        };
+        assert_eq!(summary.to_string(), "3 runs in 1s 500ms: 2 succeeded, 1 failed, 4 skipped");
+    }
+
+    #[test]
+    fn test_seeded_durations_are_stable() {
+        // Golden values: these must not change between releases, as `--seed` promises
+        // the same durations for the same seed and arguments across versions.
+        let ctx = WaitContext::seeded(42);
+        let exponential = ExponentialWait { lambda: 1.0, verbose: None, jitter: None };
+        let normal = NormalWait {
+            mean: Duration::from_secs(10),
+            std_dev: 2.0,
+            verbose: None,
+            jitter: Some(Duration::from_millis(500)),
+        };
+        let uniform = UniformWait {
+            min: Duration::from_secs(1),
+            max: Duration::from_secs(5),
             verbose: None,
             jitter: None,
         };
-        // We can't predict the exact value, but we can check if it's reasonable
-        // and doesn't panic. A simple check is that it's not negative.
-        let duration = wait.calculate_wait_duration().unwrap();
-        assert!(duration >= Duration::ZERO);
+        let backoff = StrategyBackoff {
+            strategy: BackoffStrategy::FullJitter,
+            base: Duration::from_secs(1),
+            cap: None,
+            jitter: None,
+        };
+        let samples = [
+            exponential.calculate_wait_duration(&ctx).unwrap(),
+            normal.calculate_wait_duration(&ctx).unwrap(),
+            uniform.calculate_wait_duration(&ctx).unwrap(),
+            backoff.delay(&ctx, 4, Duration::ZERO).unwrap(),
+        ];
+        assert_eq!(
+            samples,
+            [
+                Duration::from_nanos(875_883_378),
+                Duration::from_nanos(12_881_899_421),
+                Duration::from_nanos(3_509_442_085),
+                Duration::from_nanos(2_308_751_033),
+            ]
+        );
+
+        // A context with the same seed starts the sequence again.
+        let ctx = WaitContext::seeded(42);
+        assert_eq!(exponential.calculate_wait_duration(&ctx).unwrap(), samples[0]);
     }
 
     #[test]
-    fn test_exponential_wait_calculate_duration() {
-        let wait = ExponentialWait {
-            lambda: 1.0,
+    fn test_bounds_truncate_distributions() {
+        // A normal centred on zero used to pile half its samples at exactly zero.
+        let ctx = WaitContext::seeded(7);
+        let normal = NormalWait {
+            mean: Duration::ZERO,
+            std_dev: 1.0,
             verbose: None,
             jitter: None,
         };
-        let duration = wait.calculate_wait_duration().unwrap();
-        assert!(duration >= Duration::ZERO);
+        let samples: Vec<_> = (0..1000)
+            .map(|_| normal.calculate_wait_duration(&ctx).unwrap())
+            .collect();
+        assert!(samples.iter().all(|d| !d.is_zero()));
+
+        // Within the bounds, the shape is kept rather than clamped onto them.
+        let ctx = WaitContext::seeded(7)
+            .with_bounds(Some(Duration::from_secs(1)), Some(Duration::from_secs(3)));
+        let pareto = ParetoWait {
+            scale: 1.0,
+            shape: 1.0,
+            verbose: None,
+            jitter: None,
+        };
+        let samples: Vec<_> = (0..1000)
+            .map(|_| pareto.calculate_wait_duration(&ctx).unwrap())
+            .collect();
+        assert!(
+            samples
+                .iter()
+                .all(|d| (Duration::from_secs(1)..Duration::from_secs(3)).contains(d))
+        );
+        let below_2s = samples
+            .iter()
+            .filter(|d| **d < Duration::from_secs(2))
+            .count();
+        // P(X < 2 | 1 <= X <= 3) = (1 - 1/2) / (1 - 1/3) = 0.75 for Pareto(1, 1).
+        assert!((700..800).contains(&below_2s), "{below_2s}");
+
+        // Jitter is added after truncation.
+        let ctx = WaitContext::seeded(7).with_bounds(None, Some(Duration::from_secs(1)));
+        let uniform = UniformWait {
+            min: Duration::ZERO,
+            max: Duration::from_secs(2),
+            verbose: None,
+            jitter: Some(Duration::from_secs(1)),
+        };
+        assert!(uniform.calculate_wait_duration(&ctx).unwrap() <= Duration::from_secs(2));
+
+        let ctx = WaitContext::seeded(7).with_bounds(Some(Duration::from_secs(60)), None);
+        let err = normal.calculate_wait_duration(&ctx).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no sample fell between 1m and infinity in 10000 tries; the bounds are too far into the distribution's tails"
+        );
+
+        // Samples of inf, which no Duration can hold, are drawn again rather than slept on.
+        let ctx = WaitContext::seeded(7);
+        let exponential = ExponentialWait { lambda: 0.0, verbose: None, jitter: None };
+        assert!(exponential.calculate_wait_duration(&ctx).is_err());
+        let triangular = TriangularWait { min: -3.0, max: -1.0, mode: -2.0, verbose: None, jitter: None };
+        assert!(triangular.calculate_wait_duration(&ctx).is_err());
     }
 
     #[test]
-    fn test_log_normal_wait_calculate_duration() {
-        let wait = LogNormalWait {
-            mean: Duration::from_secs(1),
-            std_dev: 0.1,
+    fn test_cancelled_wait_reports_time_left() {
+        let (clock, ctx) = virtual_ctx();
+        let ctx = ctx.with_cancellation({
+            let token = crate::context::CancellationToken::new();
+            token.cancel();
+            token
+        });
+        let wait = DurationWait { duration: Duration::from_secs(90), verbose: Some(Duration::from_secs(10)), jitter: None };
+        let err = wait.wait(&ctx).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<CancelledError>(),
+            Some(&CancelledError { remaining: Some(Duration::from_secs(90)) })
+        );
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_cancellation_ends_event_waits() {
+        let token = crate::context::CancellationToken::new();
+        let ctx = WaitContext::system().with_cancellation(token.clone());
+        let dir = tempfile::tempdir().unwrap();
+        let wait = FileExistsWait {
+            path: dir.path().join("never"),
+            poll_interval: Duration::from_secs(3600),
+            timeout: None,
             verbose: None,
-            jitter: None,
         };
-        let duration = wait.calculate_wait_duration().unwrap();
-        assert!(duration >= Duration::ZERO);
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        });
+        let start_time = Instant::now();
+        assert!(wait.wait(&ctx).unwrap_err().is::<CancelledError>());
+        assert!(start_time.elapsed() < Duration::from_secs(5));
+        canceller.join().unwrap();
     }
 
     #[test]
-    fn test_pareto_wait_calculate_duration() {
-        let wait = ParetoWait {
-            scale: 1.0,
-            shape: 1.0,
+    fn test_controls_move_timed_waits() {
+        let (clock, ctx) = virtual_ctx();
+        ctx.cancellation().send(Control::Extend(Duration::from_secs(60)));
+        ctx.cancellation().send(Control::Shorten(Duration::from_secs(15)));
+        let wait = DurationWait { duration: Duration::from_secs(90), verbose: None, jitter: None };
+        wait.wait(&ctx).unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(135));
+
+        ctx.cancellation().send(Control::Skip);
+        wait.wait(&ctx).unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(135));
+    }
+
+    #[test]
+    fn test_skip_ends_event_waits() {
+        let token = crate::context::CancellationToken::new();
+        let ctx = WaitContext::system().with_cancellation(token.clone());
+        let dir = tempfile::tempdir().unwrap();
+        let wait = FileExistsWait {
+            path: dir.path().join("never"),
+            poll_interval: Duration::from_secs(3600),
+            timeout: None,
             verbose: None,
+        };
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.send(Control::Skip);
+        });
+        let start_time = Instant::now();
+        wait.wait(&ctx).unwrap();
+        assert!(start_time.elapsed() < Duration::from_secs(5));
+        sender.join().unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_wait_async_on_virtual_clock() {
+        let (clock, ctx) = virtual_ctx();
+        let wait = DurationWait {
+            duration: Duration::from_secs(3600),
+            verbose: Some(Duration::from_nanos(1)),
             jitter: None,
         };
-        let duration = wait.calculate_wait_duration().unwrap();
-        assert!(duration >= Duration::ZERO);
+        wait.wait_async(&ctx).await.unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(3600));
+
+        let skipped = ProbabilisticWait {
+            inner: Box::new(DurationWait { duration: Duration::from_secs(60), verbose: None, jitter: None }),
+            probability: 0.0,
+            verbose: None,
+        };
+        assert_eq!(skipped.wait_outcome_async(&ctx).await.unwrap(), WaitOutcome::Skipped);
+        assert_eq!(clock.elapsed(), Duration::from_secs(3600));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_wait_async_is_cancelled_by_dropping_the_future() {
+        let ctx = WaitContext::system();
+        let wait = DurationWait { duration: Duration::from_secs(60), verbose: None, jitter: None };
+        let start_time = Instant::now();
+        let result = tokio::time::timeout(Duration::from_millis(50), wait.wait_async(&ctx)).await;
+        assert!(result.is_err());
+        assert!(start_time.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_wait_async_acts_on_controls_and_cancellation() {
+        let (clock, ctx) = virtual_ctx();
+        ctx.cancellation().send(Control::Extend(Duration::from_secs(60)));
+        ctx.cancellation().send(Control::Shorten(Duration::from_secs(15)));
+        let wait = DurationWait { duration: Duration::from_secs(90), verbose: Some(Duration::from_secs(10)), jitter: None };
+        wait.wait_async(&ctx).await.unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(135));
+
+        ctx.cancellation().send(Control::Skip);
+        wait.wait_async(&ctx).await.unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(135));
+
+        let token = crate::context::CancellationToken::new();
+        let ctx = WaitContext::system().with_cancellation(token.clone());
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        });
+        let start_time = Instant::now();
+        let err = wait.wait_async(&ctx).await.unwrap_err();
+        let remaining = err.downcast_ref::<CancelledError>().unwrap().remaining.unwrap();
+        assert!(remaining > Duration::from_secs(80));
+        assert!(start_time.elapsed() < Duration::from_secs(5));
+        canceller.join().unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_port_wait_async_succeeds_when_listening() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let wait = PortWait {
+            address: listener.local_addr().unwrap().to_string(),
+            poll_interval: fixed_interval(Duration::from_millis(10)),
+            connect_timeout: Duration::from_secs(1),
+            timeout: Some(Duration::from_secs(5)),
+            verbose: None,
+        };
+        wait.wait_async(&WaitContext::system()).await.unwrap();
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn test_retry_wait_async_propagates_final_exit_code() {
+        let (clock, ctx) = virtual_ctx();
+        let err = retry_shell("exit 3", Some(3), None).wait_async(&ctx).await.unwrap_err();
+        let failed = err.downcast_ref::<CommandFailedError>().unwrap();
+        assert_eq!(failed.attempts, 3);
+        assert_eq!(failed.exit_code(), 3);
+        assert_eq!(clock.elapsed(), Duration::from_millis(20));
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn test_repeat_wait_async_stops_when_cancelled() {
+        let (clock, ctx) = virtual_ctx();
+        let repeat = RepeatWait {
+            command: vec!["true".into()],
+            interval: seconds(60),
+            count: None,
+            mode: RepeatMode::FixedDelay,
+            overlap: OverlapPolicy::Skip,
+            verbose: None,
+        };
+        ctx.cancellation().cancel();
+        assert!(repeat.wait_async(&ctx).await.unwrap_err().is::<CancelledError>());
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn test_file_exists_wait_async_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let wait = FileExistsWait {
+            path: dir.path().join("never"),
+            poll_interval: Duration::from_millis(10),
+            timeout: Some(Duration::from_millis(100)),
+            verbose: None,
+        };
+        let (clock, ctx) = virtual_ctx();
+        assert!(wait.wait_async(&ctx).await.unwrap_err().is::<TimeoutError>());
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
     }
 
     #[test]
-    fn test_uniform_wait_calculate_duration() {
-        let wait = UniformWait {
-            min: Duration::from_secs(1),
-            max: Duration::from_secs(2),
+    fn test_clock_based_waits_read_the_context_clock() {
+        let (clock, ctx) = virtual_ctx();
+        let align = TimeAlignWait {
+            alignment: Alignment::Interval(Duration::from_secs(3600)),
+            offset: Duration::from_secs(900),
+            tz: None,
             verbose: None,
-            jitter: None,
         };
-        let duration = wait.calculate_wait_duration().unwrap();
-        assert!(duration >= Duration::from_secs(1) && duration <= Duration::from_secs(2));
+        align.wait(&ctx).unwrap();
+        assert_eq!(clock.now().to_rfc3339(), "2026-10-17T12:15:00+00:00");
+
+        let until = UntilTimeWait {
+            target: TimeTarget::Instant(DateTime::parse_from_rfc3339("2026-10-18T00:00:00Z").unwrap().to_utc()),
+            allow_past: false,
+            tz: None,
+            verbose: Some(Duration::from_nanos(1)),
+        };
+        until.wait(&ctx).unwrap();
+        assert_eq!(clock.now().to_rfc3339(), "2026-10-18T00:00:00+00:00");
+    }
+
+    fn seconds(secs: u64) -> Box<dyn WaitCondition> {
+        Box::new(DurationWait { duration: Duration::from_secs(secs), verbose: None, jitter: None })
+    }
+
+    fn aligned_to(secs: u64) -> Box<dyn WaitCondition> {
+        Box::new(TimeAlignWait {
+            alignment: Alignment::Interval(Duration::from_secs(secs)),
+            offset: Duration::ZERO,
+            tz: None,
+            verbose: None,
+        })
     }
 
     #[test]
-    fn test_triangular_wait_calculate_duration() {
-        let wait = TriangularWait {
-            min: 1.0,
-            max: 3.0,
-            mode: 2.0,
+    fn test_combinators() {
+        let (clock, ctx) = virtual_ctx();
+        let max = Max { waits: vec![seconds(30), seconds(90), seconds(60)], verbose: None };
+        assert_eq!(max.calculate_wait_duration(&ctx).unwrap(), Duration::from_secs(90));
+        let min = Min { waits: vec![seconds(30), seconds(90)], verbose: None };
+        assert_eq!(min.calculate_wait_duration(&ctx).unwrap(), Duration::from_secs(30));
+
+        // At 12:00:00, a sum aligns both waits from now, but a sequence aligns the
+        // second to when the first ends.
+        let sum = Sum { waits: vec![seconds(90), aligned_to(3600)], verbose: None };
+        assert_eq!(sum.calculate_wait_duration(&ctx).unwrap(), Duration::from_secs(3690));
+        let sequence = Sequence { steps: vec![seconds(90), aligned_to(3600)], verbose: None };
+        assert_eq!(sequence.calculate_wait_duration(&ctx).unwrap(), Duration::from_secs(3600));
+        sequence.wait(&ctx).unwrap();
+        assert_eq!(clock.now().to_rfc3339(), "2026-10-17T13:00:00+00:00");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "max only combines timed waits; use race for waits on events")]
+    fn test_combinators_reject_event_waits() {
+        let (_, ctx) = virtual_ctx();
+        let file = FileExistsWait {
+            path: PathBuf::from("/nonexistent/dozr"),
+            poll_interval: Duration::from_secs(1),
+            timeout: None,
             verbose: None,
-            jitter: None,
         };
-        let duration = wait.calculate_wait_duration().unwrap();
-        assert!(duration >= Duration::from_secs_f64(1.0) && duration <= Duration::from_secs_f64(3.0));
+        let max = Max { waits: vec![seconds(1), Box::new(file)], verbose: None };
+        let _ = max.wait(&ctx);
     }
 
     #[test]
-    fn test_gamma_wait_calculate_duration() {
-        let wait = GammaWait {
-            shape: 2.0,
-            scale: 1.0,
+    fn test_sequence_runs_event_waits_in_turn() {
+        let (clock, ctx) = virtual_ctx();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("ready"), "").unwrap();
+        let file = FileExistsWait { path: dir.path().join("ready"), poll_interval: Duration::from_secs(1), timeout: None, verbose: None };
+        let sequence = Sequence { steps: vec![seconds(30), Box::new(file), seconds(15)], verbose: None };
+        assert!(!sequence.is_timed());
+        sequence.wait(&ctx).unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(45));
+    }
+
+    #[test]
+    fn test_race_ends_with_the_first_wait() {
+        let ctx = WaitContext::system();
+        let dir = tempfile::tempdir().unwrap();
+        let never = FileExistsWait { path: dir.path().join("never"), poll_interval: Duration::from_secs(3600), timeout: None, verbose: None };
+        let race = Race {
+            waits: vec![Box::new(never), Box::new(DurationWait { duration: Duration::from_millis(50), verbose: None, jitter: None })],
             verbose: None,
-            jitter: None,
         };
-        let duration = wait.calculate_wait_duration().unwrap();
-        assert!(duration >= Duration::ZERO);
+        let start_time = Instant::now();
+        assert_eq!(race.wait_outcome(&ctx).unwrap(), WaitOutcome::Completed);
+        assert!(start_time.elapsed() < Duration::from_secs(5));
+
+        // A failure finishes the race too.
+        let timeout = FileExistsWait {
+            path: dir.path().join("never"),
+            poll_interval: Duration::from_millis(10),
+            timeout: Some(Duration::from_millis(20)),
+            verbose: None,
+        };
+        let race = Race { waits: vec![Box::new(timeout), seconds(3600)], verbose: None };
+        assert!(race.wait(&ctx).unwrap_err().is::<TimeoutError>());
+    }
+
+    #[test]
+    fn test_race_passes_on_cancellation() {
+        let token = crate::context::CancellationToken::new();
+        let ctx = WaitContext::system().with_cancellation(token.clone());
+        let race = Race { waits: vec![seconds(3600), seconds(7200)], verbose: None };
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        });
+        let start_time = Instant::now();
+        assert!(race.wait(&ctx).unwrap_err().is::<CancelledError>());
+        assert!(start_time.elapsed() < Duration::from_secs(5));
+        canceller.join().unwrap();
     }
 }
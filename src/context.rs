@@ -0,0 +1,636 @@
+//! The environment a wait runs in: a clock and a random number generator.
+//!
+//! Wait conditions never read the system time, sleep or draw random numbers
+//! themselves; they go through the [`WaitContext`] they are given. The dozr binary
+//! uses [`WaitContext::system`] (or [`WaitContext::seeded`] for `--seed`), while
+//! tests can pair a [`VirtualClock`], which advances instantly instead of sleeping,
+//! with a seeded generator to get fast and reproducible results.
+//!
+//! A context also carries a [`CancellationToken`]: cancelling it from another thread
+//! ends any wait running in the context with a [`CancelledError`], and [`Control`]s
+//! sent through it extend, shorten, pause or skip the wait while it runs.
+
+use crate::events::{JsonEvents, PrintFormat};
+use crate::progress::{ProgressStyle, Template};
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A source of time.
+pub trait Clock: Send + Sync {
+    /// The current wall-clock time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// The current monotonic time, for measuring elapsed time.
+    fn monotonic(&self) -> Instant;
+
+    /// Blocks for `duration`.
+    fn sleep(&self, duration: Duration);
+
+    /// Whether time passes on its own. Event-driven conditions only block in the
+    /// kernel (e.g. on inotify) when it does, and otherwise poll via [`Clock::sleep`].
+    fn is_real_time(&self) -> bool {
+        true
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+
+    fn monotonic(&self) -> Instant {
+        (**self).monotonic()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+
+    fn is_real_time(&self) -> bool {
+        (**self).is_real_time()
+    }
+}
+
+/// The operating system's clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn monotonic(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// A clock that only moves when slept on or advanced, so waits finish instantly.
+///
+/// Share it with an [`Arc`] to check how long a wait would have taken:
+///
+/// ```
+/// use dozr::conditions::{DurationWait, WaitCondition};
+/// use dozr::context::{VirtualClock, WaitContext};
+/// use rand::SeedableRng;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let clock = Arc::new(VirtualClock::new(chrono::Utc::now()));
+/// let ctx = WaitContext::new(clock.clone(), rand::rngs::StdRng::seed_from_u64(1));
+/// let wait = DurationWait { duration: Duration::from_secs(3600), verbose: None, jitter: None };
+/// wait.wait(&ctx).unwrap();
+/// assert_eq!(clock.elapsed(), Duration::from_secs(3600));
+/// ```
+#[derive(Debug)]
+pub struct VirtualClock {
+    start: DateTime<Utc>,
+    start_instant: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl VirtualClock {
+    /// Creates a clock that reads `start` until it is advanced.
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            start_instant: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner()) += duration;
+    }
+
+    /// How far the clock has moved since it was created.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.start + self.elapsed()
+    }
+
+    fn monotonic(&self) -> Instant {
+        self.start_instant + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+
+    fn is_real_time(&self) -> bool {
+        false
+    }
+}
+
+/// Sleeps for `duration` on `clock` without blocking the thread.
+///
+/// Only real-time clocks are slept on through tokio's timer; other clocks, such as a
+/// [`VirtualClock`], are slept on directly, since they return at once.
+#[cfg(feature = "tokio")]
+pub async fn sleep_async(clock: &dyn Clock, duration: Duration) {
+    if clock.is_real_time() {
+        tokio::time::sleep(duration).await;
+    } else {
+        clock.sleep(duration);
+        tokio::task::yield_now().await;
+    }
+}
+
+/// How often [`WaitContext::sleep_async`] checks the token on a real-time clock.
+#[cfg(feature = "tokio")]
+const ASYNC_POLL_PERIOD: Duration = Duration::from_millis(50);
+
+/// A command that adjusts a running wait, sent with [`CancellationToken::send`].
+///
+/// Timed waits act on all of them, async ones included. Event-driven waits, which
+/// have no deadline to move, only act on [`Control::Status`] and [`Control::Skip`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Print the time remaining, whether or not the wait is verbose.
+    Status,
+    /// Move the deadline later.
+    Extend(Duration),
+    /// Move the deadline earlier, ending the wait if it is now in the past.
+    Shorten(Duration),
+    /// Stop the countdown until [`Control::Resume`].
+    Pause,
+    Resume,
+    /// End the wait now, as if it had completed.
+    Skip,
+}
+
+impl FromStr for Control {
+    type Err = String;
+
+    /// Parses `status`, `pause`, `resume`, `skip`, or a duration prefixed with `+`
+    /// or `-` (e.g. `+5m`, `-30s`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let duration = |d: &str| {
+            humantime::parse_duration(d.trim())
+                .map_err(|e| format!("invalid duration in '{s}': {e}"))
+        };
+        match s {
+            "status" => Ok(Control::Status),
+            "pause" => Ok(Control::Pause),
+            "resume" => Ok(Control::Resume),
+            "skip" => Ok(Control::Skip),
+            _ => {
+                if let Some(d) = s.strip_prefix('+') {
+                    duration(d).map(Control::Extend)
+                } else if let Some(d) = s.strip_prefix('-') {
+                    duration(d).map(Control::Shorten)
+                } else {
+                    Err(format!(
+                        "unknown control '{s}': expected status, pause, resume, skip, +DURATION or -DURATION"
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// A handle for aborting or adjusting waits from another thread, e.g. a signal handler.
+///
+/// Clones share their state, so cancelling any clone cancels them all.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<(Mutex<TokenState>, Condvar)>);
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: bool,
+    controls: VecDeque<Control>,
+    /// How many controls have ever been sent, so sleepers can tell new ones apart.
+    sent: u64,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the waits using this token, waking any that are sleeping.
+    pub fn cancel(&self) {
+        self.state().cancelled = true;
+        self.0.1.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state().cancelled
+    }
+
+    /// Queues `control` for the wait using this token, waking it if it is sleeping.
+    /// A control sent between waits applies to the next one.
+    pub fn send(&self, control: Control) {
+        let mut state = self.state();
+        state.controls.push_back(control);
+        state.sent += 1;
+        self.0.1.notify_all();
+    }
+
+    /// Removes and returns the controls sent since the last call.
+    fn take_controls(&self) -> Vec<Control> {
+        self.state().controls.drain(..).collect()
+    }
+
+    /// Blocks for up to `timeout`, returning early if the token is cancelled or a
+    /// control is sent. Returns whether the token was cancelled.
+    fn wait_timeout(&self, timeout: Duration) -> bool {
+        let guard = self.state();
+        let sent = guard.sent;
+        let (guard, _) = self
+            .0
+            .1
+            .wait_timeout_while(guard, timeout, |state| {
+                !state.cancelled && state.sent == sent
+            })
+            .unwrap_or_else(|e| e.into_inner());
+        guard.cancelled
+    }
+
+    /// How many controls have ever been sent, for sleepers that poll for new ones.
+    #[cfg(feature = "tokio")]
+    fn sent(&self) -> u64 {
+        self.state().sent
+    }
+
+    fn state(&self) -> MutexGuard<'_, TokenState> {
+        self.0.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Error returned when a wait is cancelled through its [`CancellationToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CancelledError {
+    /// How much of the wait was left, if known up front.
+    pub remaining: Option<Duration>,
+}
+
+impl fmt::Display for CancelledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.remaining {
+            Some(remaining) => write!(
+                f,
+                "interrupted with {} left",
+                humantime::format_duration(remaining)
+            ),
+            None => write!(f, "interrupted"),
+        }
+    }
+}
+
+impl std::error::Error for CancelledError {}
+
+/// The clock, random number generator and cancellation token that wait conditions
+/// use, and how verbose timed waits show their progress.
+pub struct WaitContext {
+    clock: Arc<dyn Clock>,
+    rng: Arc<Mutex<Box<dyn RngCore + Send>>>,
+    cancellation: CancellationToken,
+    progress: ProgressStyle,
+    seed: Option<u64>,
+    events: Option<JsonEvents>,
+    print: Option<PrintFormat>,
+    label: Option<String>,
+    template: Option<Template>,
+    floor: Duration,
+    ceiling: Option<Duration>,
+}
+
+impl WaitContext {
+    pub fn new(clock: impl Clock + 'static, rng: impl RngCore + Send + 'static) -> Self {
+        Self {
+            clock: Arc::new(clock),
+            rng: Arc::new(Mutex::new(Box::new(rng))),
+            cancellation: CancellationToken::new(),
+            progress: ProgressStyle::default(),
+            seed: None,
+            events: None,
+            print: None,
+            label: None,
+            template: None,
+            floor: Duration::ZERO,
+            ceiling: None,
+        }
+    }
+
+    /// Uses `token` to cancel the waits run in this context.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Shows the progress of verbose timed waits in `style`, instead of as lines.
+    pub fn with_progress(mut self, style: ProgressStyle) -> Self {
+        self.progress = style;
+        self
+    }
+
+    /// Truncates the distributions sampled in this context to `floor` and `ceiling`:
+    /// samples outside them are drawn again rather than clamped, so the shape of the
+    /// distribution is kept between the bounds. Samples are never below zero.
+    pub fn with_bounds(mut self, floor: Option<Duration>, ceiling: Option<Duration>) -> Self {
+        self.floor = floor.unwrap_or(Duration::ZERO);
+        self.ceiling = ceiling;
+        self
+    }
+
+    /// The lowest and highest durations, in seconds, that distributions may sample.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            self.floor.as_secs_f64(),
+            self.ceiling.map_or(f64::INFINITY, |c| c.as_secs_f64()),
+        )
+    }
+
+    /// The system clock and a generator seeded by the operating system.
+    pub fn system() -> Self {
+        Self::new(SystemClock, StdRng::from_os_rng())
+    }
+
+    /// The system clock and a ChaCha8 generator seeded with `seed`.
+    ///
+    /// The same seed and arguments always produce the same durations. ChaCha8's
+    /// output is fixed by its specification, and the sampled values are pinned by
+    /// tests, so this holds across dozr versions too.
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..Self::new(SystemClock, ChaCha8Rng::seed_from_u64(seed))
+        }
+    }
+
+    /// The seed given to [`WaitContext::seeded`], if the context was made that way.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Emits JSON events for the waits run in this context instead of verbose text.
+    pub(crate) fn with_json_events(mut self, events: JsonEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub(crate) fn json_events(&self) -> Option<&JsonEvents> {
+        self.events.as_ref()
+    }
+
+    /// Prints the length of each timed wait on stdout in `format` before it starts.
+    pub(crate) fn with_print(mut self, format: PrintFormat) -> Self {
+        self.print = Some(format);
+        self
+    }
+
+    pub(crate) fn print_format(&self) -> Option<PrintFormat> {
+        self.print
+    }
+
+    /// Names the waits run in this context in their verbose lines and JSON events.
+    pub(crate) fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub(crate) fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Prints the lines of verbose countdowns in `template`.
+    pub(crate) fn with_template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub(crate) fn template(&self) -> Option<&Template> {
+        self.template.as_ref()
+    }
+
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub fn progress(&self) -> ProgressStyle {
+        self.progress
+    }
+
+    /// Returns a [`CancelledError`] if the context's token has been cancelled.
+    pub fn check_cancelled(&self) -> Result<(), CancelledError> {
+        if self.cancellation.is_cancelled() {
+            Err(CancelledError { remaining: None })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Removes and returns the [`Control`]s sent to the context's token since the last call.
+    pub fn take_controls(&self) -> Vec<Control> {
+        self.cancellation.take_controls()
+    }
+
+    /// Sleeps on the context's clock, returning early with a [`CancelledError`] if the
+    /// token is cancelled. On a real-time clock it also returns early, successfully,
+    /// when a [`Control`] is sent, so that the caller can act on it.
+    pub fn sleep(&self, duration: Duration) -> Result<(), CancelledError> {
+        if self.clock.is_real_time() {
+            if self.cancellation.wait_timeout(duration) {
+                return Err(CancelledError { remaining: None });
+            }
+        } else {
+            self.check_cancelled()?;
+            self.clock.sleep(duration);
+        }
+        Ok(())
+    }
+
+    /// Like [`WaitContext::sleep`], without blocking the thread.
+    ///
+    /// The token has no async wake-up, so on a real-time clock it is checked every
+    /// 50ms while sleeping.
+    #[cfg(feature = "tokio")]
+    pub async fn sleep_async(&self, duration: Duration) -> Result<(), CancelledError> {
+        if !self.clock.is_real_time() {
+            self.check_cancelled()?;
+            sleep_async(self.clock.as_ref(), duration).await;
+            return Ok(());
+        }
+        if duration.is_zero() {
+            // Yield so that a zero-length sleep in a loop does not starve other tasks.
+            tokio::task::yield_now().await;
+            return self.check_cancelled();
+        }
+        let sent = self.cancellation.sent();
+        let mut left = duration;
+        loop {
+            self.check_cancelled()?;
+            if left.is_zero() || self.cancellation.sent() != sent {
+                return Ok(());
+            }
+            let slice = left.min(ASYNC_POLL_PERIOD);
+            tokio::time::sleep(slice).await;
+            left -= slice;
+        }
+    }
+
+    /// Calls `f` with the random number generator.
+    pub fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut **rng)
+    }
+
+    /// A context for a wait that runs alongside others on another thread, with its
+    /// own `token` and a generator seeded from this one, so that a seed still
+    /// decides what it samples whatever order the waits run in.
+    pub(crate) fn fork(&self, token: CancellationToken) -> Self {
+        let seed = self.with_rng(|rng| rng.next_u64());
+        Self {
+            rng: Arc::new(Mutex::new(Box::new(ChaCha8Rng::seed_from_u64(seed)))),
+            cancellation: token,
+            ..self.on_clock(Arc::clone(&self.clock))
+        }
+    }
+
+    /// This context, generator and all, as seen on `clock`, e.g. to work out how
+    /// long a wait will be when it starts later.
+    pub(crate) fn on_clock(&self, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            rng: Arc::clone(&self.rng),
+            cancellation: self.cancellation.clone(),
+            progress: self.progress,
+            seed: self.seed,
+            events: self.events.clone(),
+            print: self.print,
+            label: self.label.clone(),
+            template: self.template.clone(),
+            floor: self.floor,
+            ceiling: self.ceiling,
+        }
+    }
+}
+
+impl Default for WaitContext {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_clock_advances_when_slept_on() {
+        let start = DateTime::parse_from_rfc3339("2026-10-17T12:00:00Z")
+            .unwrap()
+            .to_utc();
+        let clock = VirtualClock::new(start);
+        let instant = clock.monotonic();
+        clock.sleep(Duration::from_secs(90));
+        assert_eq!(clock.elapsed(), Duration::from_secs(90));
+        assert_eq!(clock.now().to_rfc3339(), "2026-10-17T12:01:30+00:00");
+        assert_eq!(clock.monotonic() - instant, Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_cancelling_wakes_a_sleeping_context() {
+        let token = CancellationToken::new();
+        let ctx = WaitContext::system().with_cancellation(token.clone());
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        });
+        let start = Instant::now();
+        assert_eq!(
+            ctx.sleep(Duration::from_secs(60)),
+            Err(CancelledError { remaining: None })
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+        canceller.join().unwrap();
+        assert!(ctx.check_cancelled().is_err());
+    }
+
+    #[test]
+    fn test_sending_a_control_wakes_a_sleeping_context() {
+        let token = CancellationToken::new();
+        let ctx = WaitContext::system().with_cancellation(token.clone());
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.send(Control::Skip);
+        });
+        let start = Instant::now();
+        assert_eq!(ctx.sleep(Duration::from_secs(60)), Ok(()));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        sender.join().unwrap();
+        assert_eq!(ctx.take_controls(), vec![Control::Skip]);
+        assert!(ctx.take_controls().is_empty());
+        assert!(ctx.check_cancelled().is_ok());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_sending_a_control_wakes_an_async_sleep() {
+        let token = CancellationToken::new();
+        let ctx = WaitContext::system().with_cancellation(token.clone());
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.send(Control::Skip);
+        });
+        let start = Instant::now();
+        assert_eq!(ctx.sleep_async(Duration::from_secs(60)).await, Ok(()));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        sender.join().unwrap();
+        assert_eq!(ctx.take_controls(), vec![Control::Skip]);
+
+        ctx.cancellation().cancel();
+        assert_eq!(
+            ctx.sleep_async(Duration::from_secs(60)).await,
+            Err(CancelledError { remaining: None })
+        );
+    }
+
+    #[test]
+    fn test_parse_control() {
+        assert_eq!("status".parse(), Ok(Control::Status));
+        assert_eq!(" pause\n".parse(), Ok(Control::Pause));
+        assert_eq!("resume".parse(), Ok(Control::Resume));
+        assert_eq!("skip".parse(), Ok(Control::Skip));
+        assert_eq!("+5m".parse(), Ok(Control::Extend(Duration::from_secs(300))));
+        assert_eq!(
+            "-30s".parse(),
+            Ok(Control::Shorten(Duration::from_secs(30)))
+        );
+        assert!(
+            "+soon"
+                .parse::<Control>()
+                .unwrap_err()
+                .contains("invalid duration")
+        );
+        assert!(
+            "stop"
+                .parse::<Control>()
+                .unwrap_err()
+                .contains("unknown control")
+        );
+    }
+}
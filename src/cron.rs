@@ -0,0 +1,358 @@
+//! A small parser and scheduler for standard cron expressions.
+//!
+//! Expressions have five fields (`minute hour day-of-month month day-of-week`), or six
+//! with a leading seconds field. Fields accept `*`, lists, ranges, steps and
+//! month/weekday names, and the usual `@hourly`-style shorthands are supported. As in
+//! Vixie cron, when both day-of-month and day-of-week are restricted, a day matches if
+//! either of them does.
+
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, LocalResult, NaiveDate, NaiveDateTime,
+    TimeZone, Timelike,
+};
+use std::str::FromStr;
+
+/// How far ahead to search before concluding that an expression never fires
+/// (e.g. "0 0 30 2 *"). Long enough to cover Feb 29 across a skipped leap year.
+const SEARCH_LIMIT_DAYS: i64 = 366 * 9;
+
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const SECONDS: Field = Field {
+    name: "seconds",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const MINUTES: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOURS: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAYS_OF_MONTH: Field = Field {
+    name: "day-of-month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTHS: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ],
+};
+const DAYS_OF_WEEK: Field = Field {
+    name: "day-of-week",
+    min: 0,
+    max: 7,
+    names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+};
+
+/// A parsed cron expression. Each field is stored as a bitmask of matching values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expanded = match s.trim().to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            _ => s.to_string(),
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => {
+                return Err(format!(
+                    "Invalid cron expression. Expected 5 or 6 fields, found {n}: {s}"
+                ));
+            }
+        };
+
+        let mut days_of_week = parse_field(rest[4], &DAYS_OF_WEEK)?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            seconds: parse_field(seconds, &SECONDS)?,
+            minutes: parse_field(rest[0], &MINUTES)?,
+            hours: parse_field(rest[1], &HOURS)?,
+            days_of_month: parse_field(rest[2], &DAYS_OF_MONTH)?,
+            months: parse_field(rest[3], &MONTHS)?,
+            days_of_week,
+            days_of_month_restricted: !rest[2].starts_with('*'),
+            days_of_week_restricted: !rest[4].starts_with('*'),
+        })
+    }
+}
+
+fn parse_field(spec: &str, field: &Field) -> Result<u64, String> {
+    let invalid = || format!("Invalid cron {} field: {spec}", field.name);
+    let value = |s: &str| -> Result<u32, String> {
+        let value = match field
+            .names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(s))
+        {
+            Some(index) => index as u32 + field.min,
+            None => s.parse().map_err(|_| invalid())?,
+        };
+        if (field.min..=field.max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(invalid())
+        }
+    };
+
+    let mut mask = 0u64;
+    for item in spec.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                Some(
+                    step.parse::<u32>()
+                        .ok()
+                        .filter(|&step| step > 0)
+                        .ok_or_else(invalid)?,
+                ),
+            ),
+            None => (item, None),
+        };
+        let (start, end) = if range == "*" {
+            (field.min, field.max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            // "5/15" means every 15 starting at 5.
+            let start = value(range)?;
+            (start, if step.is_some() { field.max } else { start })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for v in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+fn matches(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+impl CronSchedule {
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day_of_month = matches(self.days_of_month, date.day());
+        let day_of_week = matches(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.days_of_month_restricted && self.days_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+
+    /// Returns the first instant strictly after `after` that matches the schedule,
+    /// evaluated on the wall clock of `after`'s time zone.
+    ///
+    /// Wall-clock times skipped by a daylight saving transition never fire. Times
+    /// repeated by one fire once, at their first occurrence after `after`.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let mut t = after.naive_local().with_nanosecond(0)? + ChronoDuration::seconds(1);
+        let limit = t + ChronoDuration::days(SEARCH_LIMIT_DAYS);
+
+        while t <= limit {
+            if !matches(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !matches(self.hours, t.hour()) {
+                t = truncate(t, 3600) + ChronoDuration::hours(1);
+            } else if !matches(self.minutes, t.minute()) {
+                t = truncate(t, 60) + ChronoDuration::minutes(1);
+            } else if !matches(self.seconds, t.second()) {
+                t += ChronoDuration::seconds(1);
+            } else {
+                match tz.from_local_datetime(&t) {
+                    LocalResult::Single(next) => return Some(next),
+                    LocalResult::Ambiguous(earliest, latest) => {
+                        if earliest > *after {
+                            return Some(earliest);
+                        } else if latest > *after {
+                            return Some(latest);
+                        }
+                    }
+                    LocalResult::None => {}
+                }
+                t += ChronoDuration::seconds(1);
+            }
+        }
+        None
+    }
+}
+
+/// Truncates a time within its day to a multiple of `seconds`.
+fn truncate(t: NaiveDateTime, seconds: u32) -> NaiveDateTime {
+    let secs = t.num_seconds_from_midnight();
+    t.date().and_time(
+        chrono::NaiveTime::from_num_seconds_from_midnight_opt(secs - secs % seconds, 0).unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use chrono_tz::America::New_York;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        let schedule: CronSchedule = expression.parse().unwrap();
+        schedule.next_after(&utc(after)).unwrap().to_rfc3339()
+    }
+
+    #[test]
+    fn test_parse_valid_expressions() {
+        for expression in [
+            "* * * * *",
+            "*/15 * * * *",
+            "0 9-17 * * mon-fri",
+            "30 2 1,15 * *",
+            "0 0 1 jan,jul *",
+            "*/10 * * * * *",
+            "5/20 * * * *",
+            "0 0 * * 7",
+            "@daily",
+        ] {
+            assert!(expression.parse::<CronSchedule>().is_ok(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "* * * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "0 0 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "0 0 * foo *",
+        ] {
+            assert!(expression.parse::<CronSchedule>().is_err(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_next_after_steps_and_ranges() {
+        assert_eq!(
+            next("*/15 * * * *", "2026-10-17T10:07:30Z"),
+            "2026-10-17T10:15:00+00:00"
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-10-17T10:45:00Z"),
+            "2026-10-17T11:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 9-17 * * mon-fri", "2026-10-17T10:00:00Z"),
+            "2026-10-19T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 1 * *", "2026-12-15T00:00:00Z"),
+            "2027-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("*/10 * * * * *", "2026-10-17T10:00:05Z"),
+            "2026-10-17T10:00:10+00:00"
+        );
+    }
+
+    #[test]
+    fn test_next_after_day_of_month_or_day_of_week() {
+        // 2026-10-17 is a Saturday: the next match is Monday the 19th, before the 1st.
+        assert_eq!(
+            next("0 0 1 * mon", "2026-10-17T12:00:00Z"),
+            "2026-10-19T00:00:00+00:00"
+        );
+        // Only the 29th of February; the next one is in 2028.
+        assert_eq!(
+            next("0 0 29 2 *", "2026-10-17T12:00:00Z"),
+            "2028-02-29T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_next_after_never_matches() {
+        let schedule: CronSchedule = "0 0 30 2 *".parse().unwrap();
+        assert!(schedule.next_after(&utc("2026-10-17T12:00:00Z")).is_none());
+    }
+
+    #[test]
+    fn test_next_after_skips_dst_gap() {
+        // Clocks jump from 02:00 to 03:00 on 2026-03-08 in New York, so 02:30 doesn't exist that day.
+        let schedule: CronSchedule = "30 2 * * *".parse().unwrap();
+        let after = New_York.with_ymd_and_hms(2026, 3, 8, 0, 0, 0).unwrap();
+        let next = schedule.next_after(&after).unwrap();
+        assert_eq!(next.to_rfc3339(), "2026-03-09T02:30:00-04:00");
+    }
+
+    #[test]
+    fn test_next_after_fires_once_in_dst_overlap() {
+        // Clocks fall back from 02:00 to 01:00 on 2026-11-01 in New York, so 01:30 happens twice.
+        let schedule: CronSchedule = "30 1 * * *".parse().unwrap();
+        let after = New_York.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap();
+        let first = schedule.next_after(&after).unwrap();
+        assert_eq!(first.to_rfc3339(), "2026-11-01T01:30:00-04:00");
+        let half_hourly: CronSchedule = "*/30 * * * *".parse().unwrap();
+        let after_first = half_hourly.next_after(&first).unwrap();
+        assert_eq!(after_first.to_rfc3339(), "2026-11-01T02:00:00-05:00");
+        // Starting during the repeated hour still finds the second occurrence.
+        let during = utc("2026-11-01T06:10:00Z").with_timezone(&New_York);
+        assert_eq!(
+            half_hourly.next_after(&during).unwrap().to_rfc3339(),
+            "2026-11-01T01:30:00-05:00"
+        );
+    }
+}
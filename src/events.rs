@@ -0,0 +1,206 @@
+//! Reports on waits for other programs to read: newline-delimited JSON events on
+//! stderr for `--output json`, and the length of each wait on stdout for `--print`
+//! and `--dry-run`.
+//!
+//! Every timed or event-driven wait emits a `start` event and ends with `complete`,
+//! `interrupted` or `timeout`. Verbose waits also emit a `tick` each time they would
+//! have printed a progress line, a skipped probability roll emits `skip`, and a
+//! status request emits `status`. Every event has an `event` name and a `time`;
+//! durations are in seconds and times are RFC 3339.
+
+use crate::context::WaitContext;
+use crate::progress::{Progress, Reporter, end_time};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, SecondsFormat, TimeDelta, Utc};
+use serde_json::{Map, Value, json};
+use std::time::Duration;
+
+/// How dozr reports on the waits it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable lines, shown with --verbose.
+    #[default]
+    Text,
+    /// One JSON object per line for each event, whether or not --verbose is given.
+    Json,
+}
+
+/// How `--print` and `--dry-run` show the length of a wait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum PrintFormat {
+    /// A duration such as "1m 30s 250ms", which dozr accepts back as an argument.
+    #[default]
+    Humantime,
+    /// Seconds, to the millisecond.
+    Secs,
+    /// Whole milliseconds.
+    Millis,
+    /// The local time the wait ends, in RFC 3339.
+    Rfc3339,
+}
+
+impl PrintFormat {
+    /// Formats a wait of `duration` starting at `now`.
+    pub fn format(self, duration: Duration, now: DateTime<Utc>) -> Result<String> {
+        let duration = Duration::from_millis(duration.as_millis() as u64);
+        Ok(match self {
+            PrintFormat::Humantime => humantime::format_duration(duration).to_string(),
+            PrintFormat::Secs => secs(duration).to_string(),
+            PrintFormat::Millis => duration.as_millis().to_string(),
+            PrintFormat::Rfc3339 => TimeDelta::from_std(duration)
+                .ok()
+                .and_then(|delta| now.checked_add_signed(delta))
+                .ok_or_else(|| anyhow!("the wait ends too far in the future to print"))?
+                .with_timezone(&Local)
+                .to_rfc3339_opts(SecondsFormat::Millis, false),
+        })
+    }
+}
+
+/// What `start` events say about the wait being run.
+#[derive(Debug, Clone)]
+pub(crate) struct JsonEvents {
+    condition: String,
+    params: Map<String, Value>,
+    seed: Option<u64>,
+}
+
+impl JsonEvents {
+    pub fn new(condition: &str, params: Map<String, Value>, seed: Option<u64>) -> Self {
+        Self {
+            condition: condition.to_string(),
+            params,
+            seed,
+        }
+    }
+}
+
+/// Writes an `event` with `fields` if `ctx` emits JSON events.
+pub(crate) fn emit(ctx: &WaitContext, event: &str, fields: Value) {
+    if ctx.json_events().is_none() {
+        return;
+    }
+    let mut object = Map::new();
+    object.insert("event".to_string(), event.into());
+    object.insert(
+        "time".to_string(),
+        ctx.clock()
+            .now()
+            .to_rfc3339_opts(SecondsFormat::Millis, true)
+            .into(),
+    );
+    if let Some(label) = ctx.label() {
+        object.insert("label".to_string(), label.into());
+    }
+    if let Value::Object(fields) = fields {
+        object.extend(fields);
+    }
+    eprintln!("{}", Value::Object(object));
+}
+
+/// Writes a `start` event describing the wait, along with `fields`.
+pub(crate) fn emit_start(ctx: &WaitContext, fields: Value) {
+    let Some(events) = ctx.json_events() else {
+        return;
+    };
+    let mut object = Map::new();
+    object.insert("condition".to_string(), events.condition.clone().into());
+    object.insert("params".to_string(), events.params.clone().into());
+    object.insert("seed".to_string(), events.seed.into());
+    if let Value::Object(fields) = fields {
+        object.extend(fields);
+    }
+    emit(ctx, "start", Value::Object(object));
+}
+
+/// Whether to print human-readable verbose output: only when it was asked for, and
+/// never in between JSON events.
+pub(crate) fn prints_text(ctx: &WaitContext, verbose: Option<Duration>) -> bool {
+    verbose.is_some() && ctx.json_events().is_none()
+}
+
+/// Emits the events of a timed wait: `start`, `tick`s if `ticks` is set, and
+/// `complete` or `interrupted`.
+pub(crate) struct JsonReporter<'a> {
+    ctx: &'a WaitContext,
+    ticks: bool,
+    started: bool,
+}
+
+impl<'a> JsonReporter<'a> {
+    pub fn new(ctx: &'a WaitContext, ticks: bool) -> Self {
+        Self {
+            ctx,
+            ticks,
+            started: false,
+        }
+    }
+}
+
+impl Reporter for JsonReporter<'_> {
+    fn update(&mut self, progress: &Progress) {
+        if !self.started {
+            self.started = true;
+            let mut fields = json!({ "duration": secs(progress.total) });
+            // A wait that ends past the last date chrono can represent has no target.
+            if let Some(target) = end_time(self.ctx.clock().now(), progress.remaining) {
+                fields["target"] = target.to_rfc3339_opts(SecondsFormat::Millis, true).into();
+            }
+            emit_start(self.ctx, fields);
+        }
+        if progress.remaining.is_zero() {
+            let fields =
+                json!({ "elapsed": secs(progress.elapsed), "oversleep": secs(progress.overrun) });
+            emit(self.ctx, "complete", fields);
+        } else if self.ticks && !progress.elapsed.is_zero() {
+            emit(self.ctx, "tick", progress_fields(progress));
+        }
+    }
+
+    fn status(&mut self, progress: &Progress) {
+        emit(self.ctx, "status", progress_fields(progress));
+    }
+
+    fn cancelled(&mut self, progress: &Progress) {
+        let fields =
+            json!({ "elapsed": secs(progress.elapsed), "remaining": secs(progress.remaining) });
+        emit(self.ctx, "interrupted", fields);
+    }
+}
+
+fn progress_fields(progress: &Progress) -> Value {
+    json!({
+        "elapsed": secs(progress.elapsed),
+        "remaining": secs(progress.remaining),
+        "duration": secs(progress.total),
+        "paused": progress.paused,
+    })
+}
+
+/// A duration in seconds, to the millisecond.
+pub(crate) fn secs(duration: Duration) -> f64 {
+    duration.as_millis() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_formats() {
+        let now = DateTime::parse_from_rfc3339("2026-10-17T12:00:00Z")
+            .unwrap()
+            .to_utc();
+        let duration = Duration::from_micros(90_250_900);
+        let format = |format: PrintFormat| format.format(duration, now).unwrap();
+        assert_eq!(format(PrintFormat::Humantime), "1m 30s 250ms");
+        assert_eq!(format(PrintFormat::Secs), "90.25");
+        assert_eq!(format(PrintFormat::Millis), "90250");
+        let end = DateTime::parse_from_rfc3339(&format(PrintFormat::Rfc3339)).unwrap();
+        assert_eq!(
+            end.to_utc(),
+            DateTime::parse_from_rfc3339("2026-10-17T12:01:30.250Z").unwrap()
+        );
+        assert!(PrintFormat::Rfc3339.format(Duration::MAX, now).is_err());
+    }
+}
@@ -1,133 +1,490 @@
-use anyhow::Result;
-use clap::Parser;
-
+use anyhow::{Context, Result};
+use conditions::{WaitCondition, WaitOutcome};
+use context::{CancellationToken, CancelledError, Clock, Control, WaitContext};
+use events::{OutputFormat, PrintFormat};
+use progress::ProgressStyle;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::process::Command;
 
 pub mod cli;
 pub mod conditions;
+pub mod context;
+pub mod cron;
+pub mod events;
+mod platform;
+pub mod progress;
+
+/// Exit code used when an event-driven wait gives up, matching coreutils `timeout`.
+pub const EXIT_TIMEOUT: u8 = 124;
+
+/// Exit code used when dozr is stopped by SIGINT or SIGTERM, as shells report SIGINT.
+pub const EXIT_INTERRUPTED: u8 = 130;
+
+/// Exit code used with `--fail-on-skip` when the probability roll skips the wait.
+pub const EXIT_SKIPPED: u8 = 3;
+
+/// Exit code used when the trailing command exists but cannot be run, as in POSIX shells.
+pub const EXIT_CANNOT_EXECUTE: u8 = 126;
+
+/// Exit code used when the trailing command cannot be found, as in POSIX shells.
+pub const EXIT_NOT_FOUND: u8 = 127;
+
+/// Maps an error returned by [`run`] to the process exit code.
+///
+/// A `retry` that never succeeds exits with the command's own last exit code.
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    if let Some(failed) = err.downcast_ref::<conditions::CommandFailedError>() {
+        failed.exit_code()
+    } else if let Some(exec) = err.downcast_ref::<ExecError>() {
+        exec.exit_code()
+    } else if err.is::<conditions::TimeoutError>() {
+        EXIT_TIMEOUT
+    } else if err.is::<CancelledError>() {
+        EXIT_INTERRUPTED
+    } else if err.is::<SkippedError>() {
+        EXIT_SKIPPED
+    } else {
+        1
+    }
+}
+
+/// Whether the error should be reported on exit. Interruptions and skips are
+/// expected outcomes with their own exit codes, and say so in verbose output.
+pub fn is_quiet(err: &anyhow::Error) -> bool {
+    err.is::<CancelledError>() || err.is::<SkippedError>()
+}
 
 /// The main entry point for the dozr application.
 ///
 /// This function parses command-line arguments, determines the appropriate
-/// wait condition, and then executes the wait.
+/// wait condition, and then executes the wait. SIGINT and SIGTERM end the wait
+/// with a [`CancelledError`]; a second signal exits straight away. SIGUSR1 and
+/// SIGUSR2 send [`Control::Status`] and [`Control::Skip`], as does `--control-file`.
 pub fn run() -> Result<()> {
-    let args = cli::Cli::parse();
-    run_with_args(args)
+    let args = cli::Cli::parse_args();
+    let cancellation = CancellationToken::new();
+    let token = cancellation.clone();
+    platform::on_termination(move || {
+        if token.is_cancelled() {
+            std::process::exit(EXIT_INTERRUPTED.into());
+        }
+        token.cancel();
+    })?;
+    let token = cancellation.clone();
+    platform::on_control_signal(move |control| token.send(control))?;
+    if let Some(path) = &args.control_file {
+        watch_control_file(path.clone(), cancellation.clone())?;
+    }
+    run_with_args(args, cancellation)
+}
+
+/// Reads [`Control`]s from `path`, one per line, on a background thread and sends
+/// them to `token`.
+///
+/// A named pipe is reopened each time its writer closes it. A regular file is
+/// followed from its current end, like `tail -f`, so that old lines are not replayed.
+fn watch_control_file(path: PathBuf, token: CancellationToken) -> Result<()> {
+    let fifo = platform::is_fifo(&path).with_context(|| format!("cannot read control file {}", path.display()))?;
+    let send = move |line: &str| match line.parse() {
+        Ok(control) => token.send(control),
+        Err(err) => eprintln!("[DOZR] Ignoring control: {err}"),
+    };
+    std::thread::spawn(move || {
+        // Opening a named pipe blocks until a writer opens it, so it happens here.
+        while let Ok(file) = File::open(&path) {
+            let mut reader = BufReader::new(file);
+            if !fifo && reader.seek(SeekFrom::End(0)).is_err() {
+                return;
+            }
+            let mut line = String::new();
+            loop {
+                match reader.read_line(&mut line) {
+                    Ok(0) if fifo => break,
+                    Ok(0) => std::thread::sleep(std::time::Duration::from_millis(100)),
+                    // Wait for the rest of a line that is still being written.
+                    Ok(_) if !line.ends_with('\n') && !fifo => {}
+                    Ok(_) => {
+                        if !line.trim().is_empty() {
+                            send(&line);
+                        }
+                        line.clear();
+                    }
+                    Err(_) => return,
+                }
+            }
+        }
+    });
+    Ok(())
 }
 
 /// The main logic of the application, accepting a Cli object.
-fn run_with_args(args: cli::Cli) -> Result<()> {
+fn run_with_args(args: cli::Cli, cancellation: CancellationToken) -> Result<()> {
+    // JSON events report the seed, so pick one to make the run reproducible.
+    let seed = match args.output {
+        OutputFormat::Json => Some(args.seed.unwrap_or_else(rand::random)),
+        OutputFormat::Text => args.seed,
+    };
+    let mut ctx = match seed {
+        Some(seed) => WaitContext::seeded(seed),
+        None => WaitContext::system(),
+    }
+    .with_cancellation(cancellation)
+    .with_bounds(args.floor, args.ceiling);
+    // A line format asks for lines, unless --progress says otherwise.
+    let progress = args.progress.or(args.format.as_ref().map(|_| ProgressStyle::Lines));
+    ctx = ctx.with_progress(progress.unwrap_or_else(ProgressStyle::detect));
+    if let Some(label) = args.label {
+        ctx = ctx.with_label(label);
+    }
+    if let Some(template) = args.format {
+        ctx = ctx.with_template(template);
+    }
+    if args.output == OutputFormat::Json {
+        let params = args
+            .invocation
+            .params
+            .iter()
+            .map(|(name, values)| {
+                let value = match values.as_slice() {
+                    [value] => value.clone().into(),
+                    values => values.to_vec().into(),
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        ctx = ctx.with_json_events(events::JsonEvents::new(&args.invocation.command, params, seed));
+    }
+    if args.print {
+        ctx = ctx.with_print(args.print_format);
+    }
+    // Asking for a bar or lines asks for verbose output, adaptive unless -v says otherwise.
+    let verbose = match progress {
+        Some(ProgressStyle::Bar | ProgressStyle::Lines) => args.verbose.or(Some(std::time::Duration::from_nanos(1))),
+        _ => args.verbose,
+    };
     let condition = args
         .command
-        .into_wait_condition(args.jitter, args.verbose, args.probability);
-    condition.wait()
+        .into_wait_condition(args.jitter, verbose, args.probability, args.tz);
+    if args.dry_run {
+        return dry_run(&ctx, condition.as_ref(), args.print_format);
+    }
+    let outcome = condition.wait_outcome(&ctx)?;
+    if outcome == WaitOutcome::Skipped && args.fail_on_skip {
+        return Err(SkippedError.into());
+    }
+    if args.exec.is_empty() || (outcome == WaitOutcome::Skipped && !args.run_on_skip) {
+        return Ok(());
+    }
+    Err(exec(&args.exec).into())
 }
 
-/// Performs the wait with verbose progress updates.
-pub fn verbose_wait<F>(total_wait: std::time::Duration, update_period: std::time::Duration, mut display_fn: F)
-where
-    F: FnMut(std::time::Duration),
-{
-    let start = std::time::Instant::now();
-    let mut last_displayed_eta: Option<u64> = None;
+/// Prints how long `condition` would wait, in `format`, without waiting.
+///
+/// With a seed, the duration is the one a real run with the same arguments sleeps for.
+fn dry_run(ctx: &WaitContext, condition: &dyn WaitCondition, format: PrintFormat) -> Result<()> {
+    if !condition.is_timed() {
+        anyhow::bail!("--dry-run only prints timed waits; waits for events have no fixed length");
+    }
+    let duration = condition.calculate_wait_duration(ctx)?;
+    if let Some(description) = condition.describe() {
+        eprintln!("{description}");
+    }
+    println!("{}", format.format(duration, ctx.clock().now())?);
+    Ok(())
+}
 
-    loop {
-        let elapsed = start.elapsed();
-        let remaining = total_wait.saturating_sub(elapsed);
-        let eta = remaining.as_secs_f64();
-        let rounded_eta = eta.round() as u64;
-
-        if remaining == std::time::Duration::ZERO {
-            display_fn(std::time::Duration::ZERO);
-            break;
-        }
+/// Error returned with `--fail-on-skip` when the probability roll skips the wait.
+#[derive(Debug)]
+pub struct SkippedError;
 
-        // Only display if ETA has changed or it's the very first display
-        if last_displayed_eta.map_or(true, |last_eta| last_eta != rounded_eta) {
-            display_fn(std::time::Duration::from_secs(rounded_eta));
-            last_displayed_eta = Some(rounded_eta);
-        }
+impl fmt::Display for SkippedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wait skipped by the probability roll")
+    }
+}
 
-        let next_update_time = elapsed + update_period;
-        let sleep_duration = next_update_time.saturating_sub(elapsed);
+impl std::error::Error for SkippedError {}
 
-        if sleep_duration > std::time::Duration::ZERO {
-            std::thread::sleep(sleep_duration);
-        } else if remaining > std::time::Duration::ZERO {
-            // If sleep_duration is zero or negative, but there's still time remaining,
-            // yield to ensure other threads can run and prevent busy-waiting.
-            std::thread::yield_now();
+/// Error returned when the trailing command cannot be started.
+#[derive(Debug)]
+pub struct ExecError {
+    pub program: OsString,
+    pub source: io::Error,
+}
+
+impl ExecError {
+    pub fn exit_code(&self) -> u8 {
+        if self.source.kind() == io::ErrorKind::NotFound {
+            EXIT_NOT_FOUND
         } else {
-            // If no time remaining, break the loop
-            break;
+            EXIT_CANNOT_EXECUTE
         }
     }
 }
 
-/// Performs the wait with adaptive verbose progress updates.
-pub fn adaptive_verbose_wait<F>(total_wait: std::time::Duration, mut display_fn: F)
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to run {}", self.program.to_string_lossy())
+    }
+}
+
+impl std::error::Error for ExecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Replaces dozr with `command`, returning only if it cannot be started.
+fn exec(command: &[OsString]) -> ExecError {
+    let (program, args) = command.split_first().expect("exec is only called with a command");
+    ExecError {
+        program: program.clone(),
+        source: platform::exec(Command::new(program).args(args)),
+    }
+}
+
+/// Performs the wait in `ctx` with verbose progress updates.
+///
+/// [`Control`]s sent to the context's token move the deadline while the wait runs;
+/// [`Control::Status`] displays the time remaining even if it has not changed.
+/// Returns a [`CancelledError`] with the time left if the token is cancelled.
+pub fn verbose_wait<F>(
+    ctx: &WaitContext,
+    total_wait: std::time::Duration,
+    update_period: std::time::Duration,
+    mut display_fn: F,
+) -> Result<(), CancelledError>
+where
+    F: FnMut(std::time::Duration),
+{
+    let sleep = |duration: std::time::Duration| {
+        if duration > std::time::Duration::ZERO {
+            ctx.sleep(duration)
+        } else {
+            // Yield to ensure other threads can run and prevent busy-waiting.
+            std::thread::yield_now();
+            ctx.check_cancelled()
+        }
+    };
+    let period = |remaining: std::time::Duration| update_period.min(remaining);
+    countdown_wait(ctx, total_wait, &mut progress::Lines::new(|progress: &progress::Progress| display_fn(progress.remaining)), period, sleep)
+}
+
+/// Performs the wait in `ctx` with adaptive verbose progress updates, acting on
+/// [`Control`]s and cancellation like [`verbose_wait`].
+pub fn adaptive_verbose_wait<F>(ctx: &WaitContext, total_wait: std::time::Duration, mut display_fn: F) -> Result<(), CancelledError>
 where
     F: FnMut(std::time::Duration),
 {
-    let start = std::time::Instant::now();
-    let mut last_displayed_eta: Option<u64> = None;
+    let sleep = |duration| ctx.sleep(duration);
+    countdown_wait(ctx, total_wait, &mut progress::Lines::new(|progress: &progress::Progress| display_fn(progress.remaining)), adaptive_sleep_duration, sleep)
+}
 
+/// Counts down `total_wait` in `ctx`, updating `reporter` and calling `sleep` with
+/// `period(remaining)` between updates.
+///
+/// Before each update, the [`Control`]s sent to the context are applied in turn,
+/// with [`Control::Status`] passed on to `reporter`. If `sleep` fails, the countdown
+/// stops with a [`CancelledError`] carrying the time left.
+pub(crate) fn countdown_wait<P, S>(
+    ctx: &WaitContext,
+    total_wait: std::time::Duration,
+    reporter: &mut dyn progress::Reporter,
+    mut period: P,
+    mut sleep: S,
+) -> Result<(), CancelledError>
+where
+    P: FnMut(std::time::Duration) -> std::time::Duration,
+    S: FnMut(std::time::Duration) -> Result<(), CancelledError>,
+{
+    let mut deadline = Deadline::new(ctx.clock().monotonic(), total_wait);
     loop {
-        let elapsed = start.elapsed();
-        let remaining = total_wait.saturating_sub(elapsed);
-        let eta = remaining.as_secs_f64();
-        let rounded_eta = eta.round() as u64;
-
-        if remaining == std::time::Duration::ZERO {
-            display_fn(std::time::Duration::ZERO);
-            break;
+        let progress = deadline.update(ctx, reporter);
+        if progress.remaining.is_zero() {
+            return Ok(());
         }
+        if sleep(period(progress.remaining)).is_err() {
+            return Err(deadline.cancel(ctx, reporter));
+        }
+    }
+}
 
-        // Only display if ETA has changed or it's the very first display
-        if last_displayed_eta.map_or(true, |last_eta| last_eta != rounded_eta) {
-            display_fn(std::time::Duration::from_secs(rounded_eta));
-            last_displayed_eta = Some(rounded_eta);
+/// Like [`countdown_wait`], sleeping with [`WaitContext::sleep_async`] so as not to
+/// block the thread.
+#[cfg(feature = "tokio")]
+pub(crate) async fn countdown_wait_async<P>(
+    ctx: &WaitContext,
+    total_wait: std::time::Duration,
+    reporter: &mut (dyn progress::Reporter + Send),
+    mut period: P,
+) -> Result<(), CancelledError>
+where
+    P: FnMut(std::time::Duration) -> std::time::Duration,
+{
+    let mut deadline = Deadline::new(ctx.clock().monotonic(), total_wait);
+    loop {
+        let progress = deadline.update(ctx, reporter);
+        if progress.remaining.is_zero() {
+            return Ok(());
         }
+        if ctx.sleep_async(period(progress.remaining)).await.is_err() {
+            return Err(deadline.cancel(ctx, reporter));
+        }
+    }
+}
 
-        let current_update_period = get_adaptive_update_period(remaining);
+/// The end of a countdown, which [`Control`]s can move or pause.
+struct Deadline {
+    start: std::time::Instant,
+    /// The length of the wait, including any changes made by controls.
+    total: std::time::Duration,
+    /// The time remaining as of `since`.
+    left: std::time::Duration,
+    since: std::time::Instant,
+    paused: bool,
+}
 
-        let remaining_secs = remaining.as_secs();
+impl Deadline {
+    fn new(start: std::time::Instant, total_wait: std::time::Duration) -> Self {
+        Self { start, total: total_wait, left: total_wait, since: start, paused: false }
+    }
 
-        let time_to_next_marker = if current_update_period.as_secs() == 0 {
-            remaining
+    fn remaining(&self, now: std::time::Instant) -> std::time::Duration {
+        if self.paused {
+            self.left
         } else {
-            let target_marker_secs = (remaining_secs / current_update_period.as_secs()) * current_update_period.as_secs();
-            remaining.saturating_sub(std::time::Duration::from_secs(target_marker_secs))
-        };
+            self.left.saturating_sub(now.saturating_duration_since(self.since))
+        }
+    }
 
-        let time_to_next_threshold = if remaining_secs > 600 {
-            remaining.saturating_sub(std::time::Duration::from_secs(600))
-        } else if remaining_secs > 300 {
-            remaining.saturating_sub(std::time::Duration::from_secs(300))
-        } else if remaining_secs > 60 {
-            remaining.saturating_sub(std::time::Duration::from_secs(60))
-        } else if remaining_secs > 20 {
-            remaining.saturating_sub(std::time::Duration::from_secs(20))
-        } else {
-            remaining
-        };
+    fn progress(&self, now: std::time::Instant) -> progress::Progress {
+        let since = now.saturating_duration_since(self.since);
+        progress::Progress {
+            elapsed: now.saturating_duration_since(self.start),
+            remaining: self.remaining(now),
+            total: self.total,
+            paused: self.paused,
+            overrun: if self.paused { std::time::Duration::ZERO } else { since.saturating_sub(self.left) },
+        }
+    }
 
-        let sleep_duration = std::cmp::min(current_update_period, std::cmp::min(time_to_next_threshold, time_to_next_marker));
-        let sleep_duration = sleep_duration.max(std::time::Duration::from_millis(1)); // Ensure at least 1ms sleep to avoid busy-waiting
+    /// Applies the controls sent to `ctx`, passing [`Control::Status`] on to
+    /// `reporter`, then reports the progress made.
+    fn update(&mut self, ctx: &WaitContext, reporter: &mut dyn progress::Reporter) -> progress::Progress {
+        let now = ctx.clock().monotonic();
+        for control in ctx.take_controls() {
+            match control {
+                Control::Status => reporter.status(&self.progress(now)),
+                control => self.apply(control, now),
+            }
+        }
+        let progress = self.progress(now);
+        reporter.update(&progress);
+        progress
+    }
 
-        if sleep_duration > std::time::Duration::ZERO {
-            std::thread::sleep(sleep_duration);
-        } else if remaining > std::time::Duration::ZERO {
-            // If sleep_duration is zero or negative, but there's still time remaining,
-            // yield to ensure other threads can run and prevent busy-waiting.
-            std::thread::yield_now();
-        } else {
-            // If no time remaining, break the loop
-            break;
+    /// Reports that the countdown was cancelled, returning the error for it.
+    fn cancel(&self, ctx: &WaitContext, reporter: &mut dyn progress::Reporter) -> CancelledError {
+        let progress = self.progress(ctx.clock().monotonic());
+        reporter.cancelled(&progress);
+        CancelledError { remaining: Some(progress.remaining) }
+    }
+
+    fn apply(&mut self, control: Control, now: std::time::Instant) {
+        self.left = self.remaining(now);
+        self.since = now;
+        // Keep the time done so far, `total - left`, as it is.
+        let shorten = |deadline: &mut Self, duration: std::time::Duration| {
+            let duration = duration.min(deadline.left);
+            deadline.left -= duration;
+            deadline.total = deadline.total.saturating_sub(duration);
+        };
+        match control {
+            Control::Extend(duration) => {
+                self.left = self.left.saturating_add(duration);
+                self.total = self.total.saturating_add(duration);
+            }
+            Control::Shorten(duration) => shorten(self, duration),
+            Control::Pause => self.paused = true,
+            Control::Resume => self.paused = false,
+            Control::Skip => {
+                shorten(self, self.left);
+                self.paused = false;
+            }
+            Control::Status => {}
         }
     }
 }
 
-fn get_adaptive_update_period(remaining: std::time::Duration) -> std::time::Duration {
+/// Like [`verbose_wait`], without blocking the thread.
+///
+/// Dropping the future also cancels the wait.
+#[cfg(feature = "tokio")]
+pub async fn verbose_wait_async<F>(
+    ctx: &WaitContext,
+    total_wait: std::time::Duration,
+    update_period: std::time::Duration,
+    mut display_fn: F,
+) -> Result<(), CancelledError>
+where
+    F: FnMut(std::time::Duration) + Send,
+{
+    let mut lines = progress::Lines::new(|progress: &progress::Progress| display_fn(progress.remaining));
+    let period = |remaining: std::time::Duration| update_period.min(remaining);
+    countdown_wait_async(ctx, total_wait, &mut lines, period).await
+}
+
+/// Like [`adaptive_verbose_wait`], without blocking the thread.
+///
+/// Dropping the future also cancels the wait.
+#[cfg(feature = "tokio")]
+pub async fn adaptive_verbose_wait_async<F>(
+    ctx: &WaitContext,
+    total_wait: std::time::Duration,
+    mut display_fn: F,
+) -> Result<(), CancelledError>
+where
+    F: FnMut(std::time::Duration) + Send,
+{
+    let mut lines = progress::Lines::new(|progress: &progress::Progress| display_fn(progress.remaining));
+    countdown_wait_async(ctx, total_wait, &mut lines, adaptive_sleep_duration).await
+}
+
+/// How long adaptive verbose output sleeps before its next update, so that updates
+/// land on whole multiples of the current update period.
+pub(crate) fn adaptive_sleep_duration(remaining: std::time::Duration) -> std::time::Duration {
+    let current_update_period = get_adaptive_update_period(remaining);
+
+    let remaining_secs = remaining.as_secs();
+
+    let time_to_next_marker = if current_update_period.as_secs() == 0 {
+        remaining
+    } else {
+        let target_marker_secs = (remaining_secs / current_update_period.as_secs()) * current_update_period.as_secs();
+        remaining.saturating_sub(std::time::Duration::from_secs(target_marker_secs))
+    };
+
+    let time_to_next_threshold = if remaining_secs > 600 {
+        remaining.saturating_sub(std::time::Duration::from_secs(600))
+    } else if remaining_secs > 300 {
+        remaining.saturating_sub(std::time::Duration::from_secs(300))
+    } else if remaining_secs > 60 {
+        remaining.saturating_sub(std::time::Duration::from_secs(60))
+    } else if remaining_secs > 20 {
+        remaining.saturating_sub(std::time::Duration::from_secs(20))
+    } else {
+        remaining
+    };
+
+    let sleep_duration = std::cmp::min(current_update_period, std::cmp::min(time_to_next_threshold, time_to_next_marker));
+    sleep_duration.max(std::time::Duration::from_millis(1)) // Ensure at least 1ms sleep to avoid busy-waiting
+}
+
+pub(crate) fn get_adaptive_update_period(remaining: std::time::Duration) -> std::time::Duration {
     let remaining_secs = remaining.as_secs();
 
     if remaining_secs <= 20 {
@@ -150,26 +507,122 @@ mod tests {
     use super::*;
     use std::time::Duration;
     use crate::cli::{Cli, Commands};
+    use crate::events::{OutputFormat, PrintFormat};
+    use crate::context::VirtualClock;
+    use rand::SeedableRng;
+    use std::sync::Arc;
+
+    fn virtual_ctx() -> (Arc<VirtualClock>, WaitContext) {
+        let clock = Arc::new(VirtualClock::new(chrono::Utc::now()));
+        let ctx = WaitContext::new(clock.clone(), rand_chacha::ChaCha8Rng::seed_from_u64(7));
+        (clock, ctx)
+    }
 
     #[test]
     fn test_verbose_wait() {
-        let total_wait = Duration::from_millis(100);
-        let update_period = Duration::from_millis(10);
-        let mut call_count = 0;
-        verbose_wait(total_wait, update_period, |_| {
-            call_count += 1;
+        let (clock, ctx) = virtual_ctx();
+        let total_wait = Duration::from_secs(10);
+        let update_period = Duration::from_secs(3);
+        let mut displayed = Vec::new();
+        verbose_wait(&ctx, total_wait, update_period, |remaining| {
+            displayed.push(remaining.as_secs());
+        })
+        .unwrap();
+        assert_eq!(displayed, vec![10, 7, 4, 1, 0]);
+        assert_eq!(clock.elapsed(), total_wait);
+    }
+
+    #[test]
+    fn test_verbose_wait_applies_controls() {
+        let (clock, ctx) = virtual_ctx();
+        let token = ctx.cancellation().clone();
+        token.send(Control::Extend(Duration::from_secs(5)));
+        let mut displayed = Vec::new();
+        verbose_wait(&ctx, Duration::from_secs(10), Duration::from_secs(3), |remaining| {
+            displayed.push(remaining.as_secs());
+            if remaining == Duration::from_secs(9) {
+                token.send(Control::Shorten(Duration::from_secs(4)));
+                token.send(Control::Status);
+            }
+        })
+        .unwrap();
+        assert_eq!(displayed, vec![15, 12, 9, 2, 0]);
+        assert_eq!(clock.elapsed(), Duration::from_secs(11));
+
+        ctx.cancellation().send(Control::Skip);
+        adaptive_verbose_wait(&ctx, Duration::from_secs(3600), |_| {}).unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(11));
+    }
+
+    #[test]
+    fn test_verbose_wait_reports_time_left_when_cancelled() {
+        let (_, ctx) = virtual_ctx();
+        let token = ctx.cancellation().clone();
+        let result = verbose_wait(&ctx, Duration::from_secs(10), Duration::from_secs(3), |remaining| {
+            if remaining == Duration::from_secs(4) {
+                token.cancel();
+            }
         });
-        assert!(call_count > 0);
+        assert_eq!(result, Err(CancelledError { remaining: Some(Duration::from_secs(4)) }));
+    }
+
+    #[test]
+    fn test_deadline_pause_and_resume() {
+        let start = std::time::Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut deadline = Deadline::new(start, Duration::from_secs(60));
+        deadline.apply(Control::Pause, at(10));
+        assert_eq!(deadline.remaining(at(40)), Duration::from_secs(50));
+        deadline.apply(Control::Extend(Duration::from_secs(5)), at(40));
+        deadline.apply(Control::Resume, at(45));
+        assert_eq!(deadline.remaining(at(55)), Duration::from_secs(45));
+        deadline.apply(Control::Pause, at(55));
+        deadline.apply(Control::Skip, at(60));
+        assert!(!deadline.paused);
+        assert_eq!(deadline.remaining(at(60)), Duration::ZERO);
     }
 
     #[test]
     fn test_adaptive_verbose_wait() {
-        let total_wait = Duration::from_secs(5);
-        let mut call_count = 0;
-        adaptive_verbose_wait(total_wait, |_| {
-            call_count += 1;
-        });
-        assert!(call_count > 0);
+        let (clock, ctx) = virtual_ctx();
+        let total_wait = Duration::from_secs(90);
+        let mut displayed = Vec::new();
+        adaptive_verbose_wait(&ctx, total_wait, |remaining| {
+            displayed.push(remaining.as_secs());
+        })
+        .unwrap();
+        assert_eq!(displayed.first(), Some(&90));
+        assert_eq!(displayed.last(), Some(&0));
+        assert!(displayed.contains(&60));
+        assert!(displayed.contains(&20));
+        assert_eq!(clock.elapsed(), total_wait);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_verbose_wait_async() {
+        let (clock, ctx) = virtual_ctx();
+        let mut displayed = Vec::new();
+        verbose_wait_async(&ctx, Duration::from_secs(10), Duration::from_secs(3), |remaining| {
+            displayed.push(remaining.as_secs());
+        })
+        .await
+        .unwrap();
+        assert_eq!(displayed, vec![10, 7, 4, 1, 0]);
+        assert_eq!(clock.elapsed(), Duration::from_secs(10));
+
+        let mut calls = 0;
+        adaptive_verbose_wait_async(&ctx, Duration::from_secs(90), |_| calls += 1).await.unwrap();
+        assert!(calls > 0);
+        assert_eq!(clock.elapsed(), Duration::from_secs(100));
+
+        // Controls move the deadline, and cancelling reports the time left.
+        ctx.cancellation().send(Control::Extend(Duration::from_secs(30)));
+        verbose_wait_async(&ctx, Duration::from_secs(10), Duration::from_secs(3), |_| {}).await.unwrap();
+        assert_eq!(clock.elapsed(), Duration::from_secs(140));
+        ctx.cancellation().cancel();
+        let err = adaptive_verbose_wait_async(&ctx, Duration::from_secs(90), |_| {}).await.unwrap_err();
+        assert_eq!(err, CancelledError { remaining: Some(Duration::from_secs(90)) });
     }
 
     #[test]
@@ -206,8 +659,24 @@ mod tests {
             jitter: None,
             verbose: None,
             probability: None,
+            seed: None,
+            tz: None,
+            exec: Vec::new(),
+            run_on_skip: false,
+            fail_on_skip: false,
+            control_file: None,
+            progress: None,
+            floor: None,
+            ceiling: None,
+            format: None,
+            label: None,
+            dry_run: false,
+            print: false,
+            print_format: PrintFormat::Humantime,
+            output: OutputFormat::Text,
+            invocation: Default::default(),
         };
-        assert!(run_with_args(cli).is_ok());
+        assert!(run_with_args(cli, CancellationToken::new()).is_ok());
     }
 
     #[test]
@@ -217,7 +686,23 @@ mod tests {
             jitter: None,
             verbose: None,
             probability: None,
+            seed: None,
+            tz: None,
+            exec: Vec::new(),
+            run_on_skip: false,
+            fail_on_skip: false,
+            control_file: None,
+            progress: None,
+            floor: None,
+            ceiling: None,
+            format: None,
+            label: None,
+            dry_run: false,
+            print: false,
+            print_format: PrintFormat::Humantime,
+            output: OutputFormat::Text,
+            invocation: Default::default(),
         };
-        assert!(run_with_args(cli).is_ok());
+        assert!(run_with_args(cli, CancellationToken::new()).is_ok());
     }
 }
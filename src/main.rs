@@ -1,6 +1,14 @@
-use anyhow::Result;
-use dozr::run;
+use dozr::{exit_code, is_quiet, run};
+use std::process::ExitCode;
 
-fn main() -> Result<()> {
-    run()
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if !is_quiet(&err) {
+                eprintln!("Error: {err:?}");
+            }
+            ExitCode::from(exit_code(&err))
+        }
+    }
 }
@@ -0,0 +1,370 @@
+//! Platform-specific helpers used by the event-driven wait conditions and for
+//! running the trailing command.
+//!
+//! Everything in here has a portable fallback so that callers never need their
+//! own `cfg` attributes: when a kernel facility is unavailable the helpers
+//! simply sleep for the requested period and let the caller poll again.
+
+use crate::context::Control;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+pub use linux::{PathWatcher, ProcessWatcher};
+
+#[cfg(not(target_os = "linux"))]
+pub use fallback::{PathWatcher, ProcessWatcher};
+
+/// Converts a timeout to milliseconds for `poll(2)`, rounding up so that we
+/// never wake before the requested period has elapsed.
+#[cfg(target_os = "linux")]
+fn poll_timeout_millis(timeout: Duration) -> libc::c_int {
+    let millis = timeout.as_nanos().div_ceil(1_000_000);
+    millis.min(libc::c_int::MAX as u128) as libc::c_int
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use std::ffi::CString;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+
+    /// Watches for a path to appear using inotify.
+    ///
+    /// The target itself cannot be watched until it exists, so the watch is
+    /// placed on its nearest existing ancestor directory. Any event there wakes
+    /// the waiter, which then re-checks the target.
+    pub struct PathWatcher {
+        fd: OwnedFd,
+    }
+
+    impl PathWatcher {
+        /// Creates a watcher, or returns `None` if inotify is unavailable.
+        pub fn new() -> Option<Self> {
+            // SAFETY: inotify_init1 has no memory-safety preconditions.
+            let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return None;
+            }
+            // SAFETY: `fd` is a freshly created descriptor that we exclusively own.
+            Some(Self {
+                fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            })
+        }
+
+        /// Blocks for at most `timeout`, returning early if something changes in
+        /// the nearest existing ancestor of `target`.
+        pub fn wait(&mut self, target: &Path, timeout: Duration) -> io::Result<()> {
+            let Some(dir) = nearest_existing_ancestor(target) else {
+                std::thread::sleep(timeout);
+                return Ok(());
+            };
+            let c_dir = CString::new(dir.as_os_str().as_bytes())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let mask =
+                libc::IN_CREATE | libc::IN_MOVED_TO | libc::IN_DELETE_SELF | libc::IN_MOVE_SELF;
+            // Re-adding a watch for the same directory is a no-op, so this is cheap
+            // to do on every call and follows the target as ancestors appear.
+            // SAFETY: `c_dir` is a valid NUL-terminated string for the duration of the call.
+            if unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), c_dir.as_ptr(), mask) } < 0 {
+                std::thread::sleep(timeout);
+                return Ok(());
+            }
+
+            poll_readable(&mut [pollfd(&self.fd)], timeout)?;
+            self.drain();
+            Ok(())
+        }
+
+        /// Discards any queued events; the caller re-checks the filesystem itself.
+        fn drain(&mut self) {
+            let mut buf = [0u8; 4096];
+            loop {
+                // SAFETY: `buf` is valid for writes of `buf.len()` bytes.
+                let n =
+                    unsafe { libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+                if n <= 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Watches a set of processes for exit using pidfds.
+    ///
+    /// Processes whose pidfd cannot be opened (kernels before 5.3, or the process has
+    /// already gone) fall back to checking `/proc`.
+    pub struct ProcessWatcher {
+        watched: Vec<WatchedProcess>,
+    }
+
+    struct WatchedProcess {
+        pid: u32,
+        pidfd: Option<OwnedFd>,
+        exited: bool,
+    }
+
+    impl ProcessWatcher {
+        pub fn new(pids: &[u32]) -> Self {
+            let watched = pids
+                .iter()
+                .map(|&pid| {
+                    // SAFETY: pidfd_open takes a pid and flags and has no memory-safety preconditions.
+                    let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+                    WatchedProcess {
+                        pid,
+                        // SAFETY: on success the syscall returns a new descriptor that we exclusively own.
+                        pidfd: (fd >= 0)
+                            .then(|| unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) }),
+                        exited: false,
+                    }
+                })
+                .collect();
+            Self { watched }
+        }
+
+        /// Returns how many of the watched processes have exited so far.
+        pub fn exited(&mut self) -> io::Result<usize> {
+            for process in self.watched.iter_mut().filter(|p| !p.exited) {
+                process.exited = match &process.pidfd {
+                    // A pidfd becomes readable once its process has terminated.
+                    Some(fd) => poll_readable(&mut [pollfd(fd)], Duration::ZERO)? > 0,
+                    None => !process_alive(process.pid)?,
+                };
+            }
+            Ok(self.watched.iter().filter(|p| p.exited).count())
+        }
+
+        /// Blocks for at most `timeout`, returning early if a watched process exits.
+        pub fn wait(&mut self, timeout: Duration) -> io::Result<()> {
+            let mut fds: Vec<libc::pollfd> = self
+                .watched
+                .iter()
+                .filter(|p| !p.exited)
+                .filter_map(|p| p.pidfd.as_ref().map(pollfd))
+                .collect();
+            if fds.is_empty() {
+                std::thread::sleep(timeout);
+            } else {
+                poll_readable(&mut fds, timeout)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Reports whether `pid` is running, treating zombies as exited.
+    pub fn process_alive(pid: u32) -> io::Result<bool> {
+        match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+            Ok(stat) => {
+                // The state follows the parenthesised command name, which may itself contain ')'.
+                let state = stat
+                    .rsplit_once(')')
+                    .and_then(|(_, rest)| rest.trim_start().chars().next());
+                Ok(!matches!(state, Some('Z' | 'X')))
+            }
+            Err(err)
+                if err.kind() == io::ErrorKind::NotFound && Path::new("/proc/self").exists() =>
+            {
+                Ok(false)
+            }
+            Err(_) => super::kill_probe(pid),
+        }
+    }
+
+    fn pollfd(fd: &OwnedFd) -> libc::pollfd {
+        libc::pollfd {
+            fd: fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }
+    }
+
+    /// Polls `fds` for readability, returning how many are ready.
+    fn poll_readable(fds: &mut [libc::pollfd], timeout: Duration) -> io::Result<usize> {
+        // SAFETY: `fds` is a valid slice of pollfd structures.
+        let ready = unsafe {
+            libc::poll(
+                fds.as_mut_ptr(),
+                fds.len() as libc::nfds_t,
+                poll_timeout_millis(timeout),
+            )
+        };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            return if err.kind() == io::ErrorKind::Interrupted {
+                Ok(0)
+            } else {
+                Err(err)
+            };
+        }
+        Ok(ready as usize)
+    }
+
+    fn nearest_existing_ancestor(target: &Path) -> Option<&Path> {
+        target.ancestors().skip(1).find_map(|ancestor| {
+            let ancestor = if ancestor.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                ancestor
+            };
+            ancestor.is_dir().then_some(ancestor)
+        })
+    }
+}
+
+/// Replaces the current process with `command`, returning only if that fails.
+#[cfg(unix)]
+pub fn exec(command: &mut Command) -> io::Error {
+    use std::os::unix::process::CommandExt;
+    command.exec()
+}
+
+/// Runs `command` and exits with its exit code, as the current process cannot be
+/// replaced on this platform. Returns only if the command could not be started.
+#[cfg(not(unix))]
+pub fn exec(command: &mut Command) -> io::Error {
+    match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => err,
+    }
+}
+
+/// Runs `handler` on a background thread each time SIGINT or SIGTERM arrives. The
+/// handler takes over from the default action, so the process no longer dies on them.
+#[cfg(unix)]
+pub fn on_termination(handler: impl Fn() + Send + 'static) -> io::Result<()> {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    let mut signals = signal_hook::iterator::Signals::new([SIGINT, SIGTERM])?;
+    std::thread::spawn(move || {
+        for _ in signals.forever() {
+            handler();
+        }
+    });
+    Ok(())
+}
+
+/// Does nothing: the default Ctrl-C handling is left in place on this platform.
+#[cfg(not(unix))]
+pub fn on_termination(_handler: impl Fn() + Send + 'static) -> io::Result<()> {
+    Ok(())
+}
+
+/// Runs `handler` on a background thread with [`Control::Status`] each time SIGUSR1
+/// arrives and [`Control::Skip`] each time SIGUSR2 arrives.
+#[cfg(unix)]
+pub fn on_control_signal(handler: impl Fn(Control) + Send + 'static) -> io::Result<()> {
+    use signal_hook::consts::{SIGUSR1, SIGUSR2};
+    let mut signals = signal_hook::iterator::Signals::new([SIGUSR1, SIGUSR2])?;
+    std::thread::spawn(move || {
+        for signal in signals.forever() {
+            handler(if signal == SIGUSR1 {
+                Control::Status
+            } else {
+                Control::Skip
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Does nothing: there are no user signals on this platform.
+#[cfg(not(unix))]
+pub fn on_control_signal(_handler: impl Fn(Control) + Send + 'static) -> io::Result<()> {
+    Ok(())
+}
+
+/// Whether `path` is a named pipe, which reads end of file each time its writer
+/// closes it rather than once and for all.
+#[cfg(unix)]
+pub fn is_fifo(path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::FileTypeExt;
+    Ok(std::fs::metadata(path)?.file_type().is_fifo())
+}
+
+#[cfg(not(unix))]
+pub fn is_fifo(path: &Path) -> io::Result<bool> {
+    std::fs::metadata(path).map(|_| false)
+}
+
+/// Probes for a process by sending it signal 0.
+#[cfg(unix)]
+fn kill_probe(pid: u32) -> io::Result<bool> {
+    // SAFETY: signal 0 performs error checking only and never delivers a signal.
+    if unsafe { libc::kill(pid as libc::pid_t, 0) } == 0 {
+        return Ok(true);
+    }
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::ESRCH) => Ok(false),
+        // The process exists but belongs to someone else.
+        Some(libc::EPERM) => Ok(true),
+        _ => Err(err),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod fallback {
+    use super::*;
+
+    /// Polling stand-in for platforms without inotify.
+    pub struct PathWatcher;
+
+    impl PathWatcher {
+        /// Always returns `None`; callers fall back to plain polling.
+        pub fn new() -> Option<Self> {
+            None
+        }
+
+        pub fn wait(&mut self, _target: &Path, timeout: Duration) -> io::Result<()> {
+            std::thread::sleep(timeout);
+            Ok(())
+        }
+    }
+
+    /// Polling stand-in for platforms without pidfds.
+    pub struct ProcessWatcher {
+        pids: Vec<u32>,
+        exited: Vec<bool>,
+    }
+
+    impl ProcessWatcher {
+        pub fn new(pids: &[u32]) -> Self {
+            Self {
+                pids: pids.to_vec(),
+                exited: vec![false; pids.len()],
+            }
+        }
+
+        /// Returns how many of the watched processes have exited so far.
+        pub fn exited(&mut self) -> io::Result<usize> {
+            for (pid, exited) in self.pids.iter().zip(self.exited.iter_mut()) {
+                if !*exited {
+                    *exited = !process_alive(*pid)?;
+                }
+            }
+            Ok(self.exited.iter().filter(|&&exited| exited).count())
+        }
+
+        pub fn wait(&mut self, timeout: Duration) -> io::Result<()> {
+            std::thread::sleep(timeout);
+            Ok(())
+        }
+    }
+
+    /// Reports whether `pid` is running.
+    #[cfg(unix)]
+    pub fn process_alive(pid: u32) -> io::Result<bool> {
+        super::kill_probe(pid)
+    }
+
+    #[cfg(not(unix))]
+    pub fn process_alive(_pid: u32) -> io::Result<bool> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "waiting for processes is not supported on this platform",
+        ))
+    }
+}
@@ -0,0 +1,475 @@
+//! How timed waits show their progress on stderr: a bar redrawn in place on a
+//! terminal, or a line each time the time remaining changes.
+
+use crate::conditions::{tag, timestamp};
+use crate::context::WaitContext;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, TimeDelta, TimeZone};
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How often the progress bar is redrawn, which keeps its spinner turning.
+pub(crate) const BAR_REFRESH: Duration = Duration::from_millis(100);
+
+const BAR_WIDTH: usize = 30;
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How verbose timed waits show the time remaining.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ProgressStyle {
+    /// A bar redrawn in place, with the percentage done, the time elapsed and left,
+    /// and when the wait will end.
+    Bar,
+    /// A line each time the number of seconds remaining changes.
+    #[default]
+    Lines,
+    /// No countdown at all.
+    None,
+}
+
+impl ProgressStyle {
+    /// The bar when stderr is a terminal, and lines otherwise.
+    pub fn detect() -> Self {
+        if std::io::stderr().is_terminal() {
+            ProgressStyle::Bar
+        } else {
+            ProgressStyle::Lines
+        }
+    }
+}
+
+/// A snapshot of a running countdown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Progress {
+    pub elapsed: Duration,
+    pub remaining: Duration,
+    /// The length of the wait, as moved by any controls.
+    pub total: Duration,
+    pub paused: bool,
+    /// How long ago the deadline passed, once it has.
+    pub overrun: Duration,
+}
+
+impl Progress {
+    fn fraction_done(&self) -> f64 {
+        if self.total.is_zero() {
+            return 1.0;
+        }
+        (1.0 - self.remaining.as_secs_f64() / self.total.as_secs_f64()).clamp(0.0, 1.0)
+    }
+}
+
+/// Shows a countdown as [`crate::countdown_wait`] runs it.
+pub(crate) trait Reporter {
+    /// Called after each sleep, and a last time with nothing remaining once the wait
+    /// completes.
+    fn update(&mut self, progress: &Progress);
+
+    /// Answers [`crate::context::Control::Status`].
+    fn status(&mut self, progress: &Progress);
+
+    /// Called when the wait is cancelled, before the interruption is reported.
+    fn cancelled(&mut self, _progress: &Progress) {}
+}
+
+/// Shows nothing.
+impl Reporter for () {
+    fn update(&mut self, _progress: &Progress) {}
+
+    fn status(&mut self, _progress: &Progress) {}
+}
+
+/// Calls `display_fn` with the progress, its time remaining rounded to whole seconds,
+/// whenever that changes. A status request displays it even if it has not.
+pub(crate) struct Lines<F> {
+    display_fn: F,
+    last_displayed_eta: Option<u64>,
+}
+
+impl<F: FnMut(&Progress)> Lines<F> {
+    pub fn new(display_fn: F) -> Self {
+        Self {
+            display_fn,
+            last_displayed_eta: None,
+        }
+    }
+}
+
+impl<F: FnMut(&Progress)> Reporter for Lines<F> {
+    fn update(&mut self, progress: &Progress) {
+        if progress.remaining.is_zero() {
+            (self.display_fn)(progress);
+            return;
+        }
+
+        // Only display if ETA has changed or it's the very first display
+        let rounded_eta = progress.remaining.as_secs_f64().round() as u64;
+        if self
+            .last_displayed_eta
+            .is_none_or(|last_eta| last_eta != rounded_eta)
+        {
+            (self.display_fn)(&Progress {
+                remaining: Duration::from_secs(rounded_eta),
+                ..*progress
+            });
+            self.last_displayed_eta = Some(rounded_eta);
+        }
+    }
+
+    fn status(&mut self, _progress: &Progress) {
+        // The countdown updates straight after applying controls.
+        self.last_displayed_eta = None;
+    }
+}
+
+/// Answers status requests with a timestamped line, whatever `R` shows otherwise.
+pub(crate) struct StatusLine<'a, R> {
+    ctx: &'a WaitContext,
+    inner: R,
+}
+
+impl<'a, R: Reporter> StatusLine<'a, R> {
+    pub fn new(ctx: &'a WaitContext, inner: R) -> Self {
+        Self { ctx, inner }
+    }
+}
+
+impl<R: Reporter> Reporter for StatusLine<'_, R> {
+    fn update(&mut self, progress: &Progress) {
+        self.inner.update(progress);
+    }
+
+    fn status(&mut self, progress: &Progress) {
+        print_status(self.ctx, progress);
+    }
+
+    fn cancelled(&mut self, progress: &Progress) {
+        self.inner.cancelled(progress);
+    }
+}
+
+/// A format for the lines of a verbose countdown, such as
+/// `"{now:%T} {label}: {remaining} left ({percent}%)"`.
+///
+/// `{remaining}`, `{elapsed}` and `{total}` are whole seconds (e.g. "45s"),
+/// `{percent}` is the share of the wait done, and `{now}` and `{target}` are the
+/// local time and the time the wait ends, in an optional strftime format that
+/// defaults to `%H:%M:%S`. `{label}` is the wait's label, and `{{` and `}}` are
+/// literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Now(String),
+    Target(String),
+    Label,
+    Remaining,
+    Elapsed,
+    Total,
+    Percent,
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut rest = s;
+        while let Some(brace) = rest.find(['{', '}']) {
+            text.push_str(&rest[..brace]);
+            rest = &rest[brace..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                text.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+            if rest.starts_with('}') {
+                return Err(format!("unmatched '}}' in format '{s}'"));
+            }
+            let end = rest
+                .find('}')
+                .ok_or_else(|| format!("unclosed '{{' in format '{s}'"))?;
+            let placeholder = &rest[1..end];
+            let part = match placeholder.split_once(':') {
+                Some(("now", time_format)) => Part::Now(parse_time_format(time_format)?),
+                Some(("target", time_format)) => Part::Target(parse_time_format(time_format)?),
+                Some(_) => return Err(unknown_placeholder(placeholder)),
+                None => match placeholder {
+                    "now" => Part::Now(DEFAULT_TIME_FORMAT.to_string()),
+                    "target" => Part::Target(DEFAULT_TIME_FORMAT.to_string()),
+                    "label" => Part::Label,
+                    "remaining" => Part::Remaining,
+                    "elapsed" => Part::Elapsed,
+                    "total" => Part::Total,
+                    "percent" => Part::Percent,
+                    _ => return Err(unknown_placeholder(placeholder)),
+                },
+            };
+            if !text.is_empty() {
+                parts.push(Part::Text(std::mem::take(&mut text)));
+            }
+            parts.push(part);
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Self { parts })
+    }
+}
+
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+fn parse_time_format(time_format: &str) -> Result<String, String> {
+    if StrftimeItems::new(time_format).any(|item| item == Item::Error) {
+        return Err(format!("invalid time format '{time_format}'"));
+    }
+    Ok(time_format.to_string())
+}
+
+fn unknown_placeholder(placeholder: &str) -> String {
+    format!(
+        "unknown placeholder '{{{placeholder}}}': expected now, target, label, remaining, elapsed, total or percent"
+    )
+}
+
+impl Template {
+    /// The line for `progress` at `now`.
+    pub(crate) fn render(
+        &self,
+        progress: &Progress,
+        now: DateTime<Local>,
+        label: Option<&str>,
+    ) -> String {
+        let mut line = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => line.push_str(text),
+                Part::Now(time_format) => line.push_str(&now.format(time_format).to_string()),
+                // A wait that ends past the last date chrono can represent has no target.
+                Part::Target(time_format) => match end_time(now, progress.remaining) {
+                    Some(target) => line.push_str(&target.format(time_format).to_string()),
+                    None => line.push('-'),
+                },
+                Part::Label => line.push_str(label.unwrap_or_default()),
+                Part::Remaining => {
+                    line.push_str(&format!("{:.0}s", progress.remaining.as_secs_f64()))
+                }
+                Part::Elapsed => line.push_str(&format!("{}s", progress.elapsed.as_secs())),
+                Part::Total => line.push_str(&format!("{:.0}s", progress.total.as_secs_f64())),
+                Part::Percent => line.push_str(&format!("{:.0}", progress.fraction_done() * 100.0)),
+            }
+        }
+        line
+    }
+}
+
+/// A progress bar on stderr, redrawn in place on every update.
+pub(crate) struct Bar<'a> {
+    ctx: &'a WaitContext,
+    frame: usize,
+}
+
+impl<'a> Bar<'a> {
+    pub fn new(ctx: &'a WaitContext) -> Self {
+        Self { ctx, frame: 0 }
+    }
+}
+
+impl Reporter for Bar<'_> {
+    fn update(&mut self, progress: &Progress) {
+        let now = self.ctx.clock().now().with_timezone(&Local);
+        let line = render_bar(progress, self.frame, now);
+        self.frame += 1;
+        if progress.remaining.is_zero() {
+            eprintln!("\r{line}\x1b[K");
+        } else {
+            eprint!("\r{line}\x1b[K");
+        }
+    }
+
+    fn status(&mut self, progress: &Progress) {
+        eprint!("\r\x1b[K");
+        print_status(self.ctx, progress);
+    }
+
+    fn cancelled(&mut self, _progress: &Progress) {
+        eprintln!();
+    }
+}
+
+/// Prints the time remaining in answer to a status request.
+fn print_status(ctx: &WaitContext, progress: &Progress) {
+    let remaining = progress.remaining.as_secs_f64();
+    if progress.paused {
+        eprintln!(
+            "[{}] {} Paused with {remaining:.0}s remaining",
+            timestamp(ctx),
+            tag(ctx)
+        );
+    } else {
+        eprintln!(
+            "[{}] {} Time remaining: {remaining:.0}s",
+            timestamp(ctx),
+            tag(ctx)
+        );
+    }
+}
+
+/// One frame of the progress bar, as seen at `now`.
+fn render_bar(progress: &Progress, frame: usize, now: DateTime<Local>) -> String {
+    let done = progress.fraction_done();
+    let filled = (done * BAR_WIDTH as f64) as usize;
+    let bar = if filled >= BAR_WIDTH {
+        "=".repeat(BAR_WIDTH)
+    } else {
+        format!(
+            "{}>{}",
+            "=".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled - 1)
+        )
+    };
+    let (spinner, end) = if progress.remaining.is_zero() {
+        (' ', ", done".to_string())
+    } else if progress.paused {
+        ('=', ", paused".to_string())
+    } else {
+        // A wait that ends past the last date chrono can represent shows no end.
+        let end = end_time(now, progress.remaining).map(|end| {
+            let format = if end.date_naive() == now.date_naive() {
+                "%H:%M:%S"
+            } else {
+                "%Y-%m-%d %H:%M:%S"
+            };
+            format!(", ends {}", end.format(format))
+        });
+        (SPINNER[frame % SPINNER.len()], end.unwrap_or_default())
+    };
+    format!(
+        "{spinner} [{bar}] {:>3.0}% {} elapsed, {} left{end}",
+        done * 100.0,
+        clock_time(progress.elapsed.as_secs()),
+        // Round up, so that the time left only reads zero once the wait is over.
+        clock_time(progress.remaining.as_millis().div_ceil(1000) as u64),
+    )
+}
+
+/// When a wait with `remaining` left at `now` ends, or `None` if that is too far
+/// in the future to represent.
+pub(crate) fn end_time<Tz: TimeZone>(
+    now: DateTime<Tz>,
+    remaining: Duration,
+) -> Option<DateTime<Tz>> {
+    TimeDelta::from_std(remaining)
+        .ok()
+        .and_then(|delta| now.checked_add_signed(delta))
+}
+
+/// Formats a number of seconds as `M:SS`, or `H:MM:SS` from an hour up.
+fn clock_time(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(elapsed: u64, remaining: u64) -> Progress {
+        Progress {
+            elapsed: Duration::from_secs(elapsed),
+            remaining: Duration::from_secs(remaining),
+            total: Duration::from_secs(elapsed + remaining),
+            paused: false,
+            overrun: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_render_bar() {
+        let now = Local.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        assert_eq!(
+            render_bar(&progress(15, 45), 1, now),
+            "/ [=======>                      ]  25% 0:15 elapsed, 0:45 left, ends 12:00:45"
+        );
+        assert_eq!(
+            render_bar(&progress(3600, 0), 2, now),
+            "  [==============================] 100% 1:00:00 elapsed, 0:00 left, done"
+        );
+        assert_eq!(
+            render_bar(
+                &Progress {
+                    paused: true,
+                    ..progress(0, 90)
+                },
+                3,
+                now
+            ),
+            "= [>                             ]   0% 0:00 elapsed, 1:30 left, paused"
+        );
+        assert_eq!(
+            render_bar(&progress(0, 86400), 0, now),
+            "| [>                             ]   0% 0:00 elapsed, 24:00:00 left, ends 2026-10-18 12:00:00"
+        );
+        assert_eq!(
+            render_bar(&progress(0, 9_999_999_999_999 * 3600), 1, now),
+            "/ [>                             ]   0% 0:00 elapsed, 9999999999999:00:00 left"
+        );
+    }
+
+    #[test]
+    fn test_template() {
+        let now = Local.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        let template: Template = "{now:%T} {label}: {remaining} left of {total} ({percent}%), {elapsed} in, ends {target:%H:%M} {{ok}}"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            template.render(&progress(15, 45), now, Some("backup")),
+            "12:00:00 backup: 45s left of 60s (25%), 15s in, ends 12:00 {ok}"
+        );
+        assert_eq!(
+            template.render(&progress(60, 0), now, None),
+            "12:00:00 : 0s left of 60s (100%), 60s in, ends 12:00 {ok}"
+        );
+        let target: Template = "{target}".parse().unwrap();
+        assert_eq!(target.render(&progress(0, 90), now, None), "12:01:30");
+        assert_eq!(
+            target.render(&progress(0, 9_999_999_999_999 * 3600), now, None),
+            "-"
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        let error = |s: &str| s.parse::<Template>().unwrap_err();
+        assert!(error("{eta}").starts_with("unknown placeholder '{eta}'"));
+        assert!(error("{label:x}").starts_with("unknown placeholder '{label:x}'"));
+        assert_eq!(error("{now:%Q}"), "invalid time format '%Q'");
+        assert_eq!(error("{now"), "unclosed '{' in format '{now'");
+        assert_eq!(error("done}"), "unmatched '}' in format 'done}'");
+    }
+
+    #[test]
+    fn test_render_bar_rounds_time_left_up() {
+        let now = Local.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        let progress = Progress {
+            elapsed: Duration::from_millis(754_900),
+            remaining: Duration::from_millis(100),
+            total: Duration::from_secs(755),
+            paused: false,
+            overrun: Duration::ZERO,
+        };
+        assert!(render_bar(&progress, 0, now).contains("12:34 elapsed, 0:01 left"));
+    }
+}
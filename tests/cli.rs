@@ -4,6 +4,7 @@ use predicates::str;
 use std::time::Instant;
 use std::time::Duration;
 use dozr::cli::{Cli, Commands};
+use dozr::events::{OutputFormat, PrintFormat};
 
 pub fn default_cli_args() -> Cli {
     Cli {
@@ -11,6 +12,22 @@ pub fn default_cli_args() -> Cli {
         jitter: None,
         verbose: None,
         probability: None,
+        seed: None,
+        tz: None,
+        exec: Vec::new(),
+        run_on_skip: false,
+        fail_on_skip: false,
+        control_file: None,
+        progress: None,
+        floor: None,
+        ceiling: None,
+        format: None,
+        label: None,
+        dry_run: false,
+        print: false,
+        print_format: PrintFormat::Humantime,
+        output: OutputFormat::Text,
+        invocation: Default::default(),
     }
 }
 
@@ -19,7 +36,7 @@ pub fn default_cli_args() -> Cli {
 #[test]
 fn test_jitter_flag_accepts_argument() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "0s", "--jitter", "0s"])
+    cmd.args(["d", "0s", "--jitter", "0s"])
         .assert()
         .success();
 }
@@ -30,7 +47,7 @@ fn test_jitter_adds_time() {
     let start = Instant::now();
     // We use a small base duration and a small jitter to keep the test fast.
     // The key is verifying that *some* extra time was added.
-    cmd.args(&["d", "100ms", "--jitter", "200ms"])
+    cmd.args(["d", "100ms", "--jitter", "200ms"])
         .assert()
         .success();
     let duration = start.elapsed();
@@ -45,18 +62,200 @@ fn test_jitter_adds_time() {
 fn test_verbose_output_includes_eta() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     // Use a duration long enough to ensure multiple ETA updates
-    cmd.args(&["d", "2s", "-v"])
+    cmd.args(["d", "2s", "-v"])
         .assert()
         .success()
         .stderr(str::contains("[DOZR] Time remaining:").and(str::contains("s")));
 }
 
+#[test]
+fn test_progress_styles() {
+    // Without a terminal the default is lines, and "lines" turns on verbose output.
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "1s", "--progress", "lines"])
+        .assert()
+        .success()
+        .stderr(str::contains("[DOZR] Time remaining: 1s").and(str::contains("Wait complete.")));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "500ms", "--progress", "bar"])
+        .assert()
+        .success()
+        .stderr(str::contains("\r").and(str::contains("] 100% 0:00 elapsed, 0:00 left, done")));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "1s", "-v", "--progress", "none"])
+        .assert()
+        .success()
+        .stderr(str::contains("Time remaining").not());
+}
+
+/// Parses each line of `stderr` as a JSON event.
+fn json_events(stderr: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{e}: {line}")))
+        .collect()
+}
+
+#[test]
+fn test_json_output() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let assert = cmd.args(["--seed", "5", "d", "1s", "--output", "json", "-v", "200ms"]).assert().success();
+    let events = json_events(&assert.get_output().stderr);
+    let names: Vec<_> = events.iter().map(|event| event["event"].as_str().unwrap()).collect();
+    assert_eq!(names.first(), Some(&"start"));
+    assert_eq!(names.last(), Some(&"complete"));
+    assert!(names[1..names.len() - 1].iter().all(|name| *name == "tick"), "{names:?}");
+    assert!(names.len() > 2, "{names:?}");
+
+    let start = &events[0];
+    assert_eq!(start["condition"], "duration");
+    assert_eq!(start["params"]["time"], "1s");
+    assert_eq!(start["seed"], 5);
+    assert_eq!(start["duration"], 1.0);
+    assert!(start["target"].is_string() && start["time"].is_string());
+    let complete = events.last().unwrap();
+    assert!(complete["elapsed"].as_f64().unwrap() >= 1.0);
+    assert!(complete["oversleep"].as_f64().unwrap() >= 0.0);
+
+    // Without --verbose there are no ticks, and a seed is always reported.
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let assert = cmd.args(["d", "100ms", "--output", "json"]).assert().success();
+    let events = json_events(&assert.get_output().stderr);
+    assert_eq!(events.len(), 2);
+    assert!(events[0]["seed"].is_u64());
+}
+
+#[test]
+fn test_json_output_skip_and_interrupt() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let assert = cmd.args(["d", "1s", "-p", "0", "-v", "--output", "json"]).assert().success();
+    let events = json_events(&assert.get_output().stderr);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["event"], "skip");
+    assert_eq!(events[0]["probability"], 0.0);
+
+    let output = interrupt_after(&["d", "1h", "--output", "json", "-v"], "-INT", Duration::from_millis(300));
+    assert_eq!(output.status.code(), Some(130));
+    let events = json_events(&output.stderr);
+    let interrupted = events.last().unwrap();
+    assert_eq!(interrupted["event"], "interrupted");
+    assert!(interrupted["remaining"].as_f64().unwrap() > 3500.0);
+}
+
+#[test]
+fn test_json_output_leaves_out_a_target_past_chrono_range() {
+    let output = interrupt_after(&["d", "9999999999999h", "--output", "json"], "-INT", Duration::from_millis(300));
+    assert_eq!(output.status.code(), Some(130));
+    let events = json_events(&output.stderr);
+    assert_eq!(events[0]["event"], "start");
+    assert!(events[0]["duration"].is_f64() && events[0].get("target").is_none());
+    assert_eq!(events.last().unwrap()["event"], "interrupted");
+}
+
+#[test]
+fn test_json_output_for_event_waits() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let assert = cmd
+        .args(["f", "/nonexistent/dozr-json", "--timeout", "200ms", "-i", "50ms", "--output", "json"])
+        .assert()
+        .code(124);
+    // The error message still follows the events.
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).into_owned();
+    let (events, error) = stderr.split_once("Error: ").unwrap();
+    assert!(error.starts_with("timed out"));
+    let events = json_events(events.as_bytes());
+    assert_eq!(events[0]["condition"], "file-exists");
+    assert_eq!(events[0]["params"], serde_json::json!({ "path": "/nonexistent/dozr-json", "timeout": "200ms", "interval": "50ms" }));
+    assert_eq!(events[1]["event"], "timeout");
+}
+
+#[test]
+fn test_dry_run_prints_the_duration_a_real_run_sleeps() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    let assert = cmd
+        .args(["--seed", "3", "--dry-run", "--print-format", "millis", "n", "1s", "0.2", "--", "echo", "ran"])
+        .assert()
+        .success()
+        .stdout(str::contains("ran").not());
+    assert!(start.elapsed() < Duration::from_millis(500));
+    let dry_run = String::from_utf8_lossy(&assert.get_output().stdout).trim().to_string();
+    let millis: u64 = dry_run.parse().unwrap();
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["--seed", "3", "--print", "--print-format", "millis", "n", "1s", "0.2", "--", "echo", "ran"])
+        .assert()
+        .success()
+        .stdout(format!("{dry_run}\nran\n"));
+    assert!(start.elapsed() >= Duration::from_millis(millis));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["--dry-run", "d", "1m30s", "-j", "0s"]).assert().success().stdout("1m 30s\n");
+
+    // Waits for events are rejected before anything is waited on or connected to.
+    for args in [
+        &["f", "/nonexistent/dozr-dry-run"][..],
+        &["f", "/nonexistent/dozr-dry-run", "-t", "30s"],
+        &["port", "127.0.0.1:1", "-t", "5s"],
+        &["retry", "--max-elapsed", "1m", "--", "false"],
+        &["race", "1s", "f /nonexistent/dozr-dry-run"],
+    ] {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        cmd.arg("--dry-run")
+            .args(args)
+            .assert()
+            .code(2)
+            .stdout("")
+            .stderr(str::contains("--dry-run only prints timed waits"));
+    }
+}
+
+#[test]
+fn test_dry_run_of_a_port_wait_does_not_connect() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["--dry-run", "port", &address, "-t", "5s"]).assert().code(2);
+    let accepted = listener.accept();
+    assert!(accepted.as_ref().is_err_and(|e| e.kind() == std::io::ErrorKind::WouldBlock), "{accepted:?}");
+}
+
+#[test]
+fn test_format_and_label() {
+    // A format implies lines of verbose output, even where a bar would be drawn.
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "1s", "--label", "backup", "--format", "{label}: {remaining} left of {total} ({percent}%)"])
+        .assert()
+        .success()
+        .stderr("backup: 1s left of 1s (0%)\nbackup: 0s left of 1s (100%)\n");
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "1s", "--progress", "lines", "--label", "db"])
+        .assert()
+        .success()
+        .stderr(str::contains("[DOZR] [db] Time remaining: 1s").and(str::contains("[DOZR] [db] Wait complete.")));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let assert = cmd.args(["d", "0s", "--label", "db", "--output", "json"]).assert().success();
+    assert!(json_events(&assert.get_output().stderr).iter().all(|event| event["label"] == "db"));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "1s", "--format", "{eta}"])
+        .assert()
+        .failure()
+        .stderr(str::contains("unknown placeholder '{eta}'"));
+}
+
 #[test]
 fn test_verbose_custom_update_period() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     // Test with a 1.5s wait and 500ms update period.
     let assert = cmd
-        .args(&["d", "1s500ms", "-v", "500ms"])
+        .args(["d", "1s500ms", "-v", "500ms"])
         .assert()
         .success();
     let output = assert.get_output();
@@ -71,7 +270,7 @@ fn test_verbose_adaptive_short_wait() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     // Test with a 5s wait - falls in 0-20s bucket (1s update period)
     let assert = cmd
-        .args(&["d", "5s", "-v"])
+        .args(["d", "5s", "-v"])
         .assert()
         .success();
     let output = assert.get_output();
@@ -87,7 +286,7 @@ fn test_verbose_adaptive_long_wait() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     // Test with a 21s wait - falls in 21-60s bucket (5s update period)
     let assert = cmd
-        .args(&["d", "21s", "-v"])
+        .args(["d", "21s", "-v"])
         .assert()
         .success();
     let output = assert.get_output();
@@ -101,7 +300,7 @@ fn test_verbose_adaptive_long_wait() {
 #[test]
 fn test_invalid_duration_arg() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "invalid-duration"])
+    cmd.args(["d", "invalid-duration"])
         .assert()
         .failure()
         .stderr(str::contains("error: invalid value"));
@@ -110,7 +309,7 @@ fn test_invalid_duration_arg() {
 #[test]
 fn test_invalid_jitter_arg() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s", "-j", "invalid-jitter"])
+    cmd.args(["d", "1s", "-j", "invalid-jitter"])
         .assert()
         .failure()
         .stderr(str::contains("error: invalid value"));
@@ -119,7 +318,7 @@ fn test_invalid_jitter_arg() {
 #[test]
 fn test_invalid_verbose_period_arg() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s", "-v", "invalid-period"])
+    cmd.args(["d", "1s", "-v", "invalid-period"])
         .assert()
         .failure()
         .stderr(str::contains("error: invalid value"));
@@ -128,7 +327,7 @@ fn test_invalid_verbose_period_arg() {
 #[test]
 fn test_duration_and_align_are_mutually_exclusive() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s", "a", "5s"])
+    cmd.args(["d", "1s", "a", "5s"])
         .assert()
         .failure()
         .stderr(str::contains("error: unexpected argument 'a' found"));
@@ -145,13 +344,13 @@ fn test_duration_or_align_is_required() {
 #[test]
 fn test_duration_is_valid() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s"]).assert().success();
+    cmd.args(["d", "1s"]).assert().success();
 }
 
 #[test]
 fn test_time_align_verbose_output() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["a", "5s", "-v"])
+    cmd.args(["a", "5s", "-v"])
         .assert()
         .success()
         .stderr(str::contains("[DOZR] Time remaining:").and(str::contains("s")));
@@ -160,7 +359,7 @@ fn test_time_align_verbose_output() {
 #[test]
 fn test_probabilistic_wait_verbose_output() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s", "-p", "1.0", "-v"])
+    cmd.args(["d", "1s", "-p", "1.0", "-v"])
         .assert()
         .success()
         .stderr(str::contains("[DOZR] Time remaining:").or(str::contains("Skipping sleep")));
@@ -169,16 +368,146 @@ fn test_probabilistic_wait_verbose_output() {
 #[test]
 fn test_probabilistic_wait_skip_verbose_output() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s", "-p", "0.0", "-v"])
+    cmd.args(["d", "1s", "-p", "0.0", "-v"])
         .assert()
         .success()
         .stderr(str::contains("Skipping sleep"));
 }
 
+#[test]
+fn test_probability_applies_to_every_subcommand() {
+    let start = Instant::now();
+    for args in [["n", "10s", "1"], ["u", "10s", "20s"], ["align", "1h", "-v"]] {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        cmd.args(args).args(["-p", "0", "--fail-on-skip"]).assert().code(3);
+    }
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // Jitter still applies when the roll decides to wait.
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["u", "100ms", "200ms", "-p", "1", "-j", "300ms", "--seed", "1"]).assert().success();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn test_probability_must_be_between_0_and_1() {
+    for probability in ["1.5", "-0.1", "NaN", "half"] {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        cmd.args(["d", "1s", "-p", probability])
+            .assert()
+            .failure()
+            .stderr(str::contains("Invalid probability. Expected a number from 0.0 to 1.0"));
+    }
+}
+
+#[test]
+fn test_floor_and_ceiling() {
+    for seed in 0..20 {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        let assert = cmd
+            .args([
+                "--seed",
+                &seed.to_string(),
+                "--dry-run",
+                "--print-format",
+                "millis",
+            ])
+            .args(["n", "10s", "5", "--floor", "8s", "--ceiling", "12s"])
+            .assert()
+            .success();
+        let millis: u64 = String::from_utf8_lossy(&assert.get_output().stdout)
+            .trim()
+            .parse()
+            .unwrap();
+        assert!((8000..=12000).contains(&millis), "{millis}");
+    }
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args([
+        "--dry-run",
+        "n",
+        "10s",
+        "1",
+        "--floor",
+        "12s",
+        "--ceiling",
+        "8s",
+    ])
+    .assert()
+    .failure()
+    .stderr(str::contains(
+        "--floor (12s) must not be greater than --ceiling (8s)",
+    ));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["--dry-run", "u", "1s", "2s", "--floor", "5s"])
+        .assert()
+        .failure()
+        .stderr(str::contains("no sample fell between 5s and infinity"));
+}
+
+#[test]
+fn test_combinators() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["max", "100ms", "u 300ms 400ms", "-v", "--progress", "lines"])
+        .assert()
+        .success()
+        .stderr(str::contains("Wait complete."));
+    assert!(start.elapsed() >= Duration::from_millis(300));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["--dry-run", "seq", "1s", "2s", "max 500ms 3s"]).assert().success().stdout("6s\n");
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["--dry-run", "sum", "1s", "min 2s 3s"]).assert().success().stdout("3s\n");
+
+    for combinator in ["max", "min", "sum"] {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        cmd.args([combinator, "1s", "f /nonexistent/dozr-combined"])
+            .assert()
+            .code(2)
+            .stderr(str::contains("Invalid wait specification 'f /nonexistent/dozr-combined': waits for events cannot be slept on"));
+    }
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["race", "f /nonexistent/dozr-race", "200ms", "-v"])
+        .assert()
+        .success()
+        .stderr(str::contains("Race won by wait 2"));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_combined_waits_use_global_tz_and_jitter() {
+    let end = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        let assert = cmd
+            .args(["--tz", "Asia/Kolkata", "--dry-run", "--print-format", "rfc3339"])
+            .args(args)
+            .assert()
+            .success();
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+        chrono::DateTime::parse_from_rfc3339(stdout.trim()).unwrap()
+    };
+    let bare = end(&["a", "1h"]);
+    for combined in [&["seq", "a 1h"][..], &["max", "a 1h", "0s"], &["sum", "a 1h"]] {
+        let combined_end = end(combined);
+        assert!((combined_end - bare).num_seconds().abs() < 1, "{combined:?}: {combined_end} != {bare}");
+    }
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["--seed", "1", "--dry-run", "-j", "1s", "seq", "1s", "1s"])
+        .assert()
+        .success()
+        .stdout(str::diff("2s\n").not());
+}
+
 #[test]
 fn test_jitter_zero_duration() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s", "-j", "0s"])
+    cmd.args(["d", "1s", "-j", "0s"])
         .assert()
         .success();
 }
@@ -191,7 +520,7 @@ fn test_until_time_verbose_output() {
     let target_time = now + chrono::Duration::seconds(5);
     let target_time_str = target_time.format("%H:%M:%S").to_string();
 
-    cmd.args(&["at", &target_time_str, "-v"])
+    cmd.args(["at", &target_time_str, "-v"])
         .assert()
         .success()
         .stderr(str::contains("[DOZR] Time remaining:").and(str::contains("s")));
@@ -200,7 +529,7 @@ fn test_until_time_verbose_output() {
 #[test]
 fn test_invalid_until_time_format() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["at", "invalid-time"])
+    cmd.args(["at", "invalid-time"])
         .assert()
         .failure()
         .stderr(str::contains("Invalid time format"));
@@ -209,7 +538,7 @@ fn test_invalid_until_time_format() {
 #[test]
 fn test_invalid_until_time_hour() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["at", "25:00"])
+    cmd.args(["at", "25:00"])
         .assert()
         .failure()
         .stderr(str::contains("Invalid time format"));
@@ -218,7 +547,7 @@ fn test_invalid_until_time_hour() {
 #[test]
 fn test_invalid_until_time_minute() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["at", "10:65"])
+    cmd.args(["at", "10:65"])
         .assert()
         .failure()
         .stderr(str::contains("Invalid time format"));
@@ -232,7 +561,7 @@ fn test_until_time_in_future() {
     let target_time_str = target_time.format("%H:%M:%S").to_string();
 
     let start_time = Instant::now();
-    cmd.args(&["at", &target_time_str]).assert().success();
+    cmd.args(["at", &target_time_str]).assert().success();
     let elapsed = start_time.elapsed();
 
     assert!(elapsed >= chrono::Duration::seconds(1).to_std().unwrap());
@@ -246,7 +575,7 @@ fn test_parse_time_until_hh_mm() {
     let target_time = now + chrono::Duration::minutes(1);
     let target_time_str = target_time.format("%H:%M").to_string();
 
-    cmd.args(&["at", &target_time_str]).assert().success();
+    cmd.args(["at", &target_time_str]).assert().success();
 }
 
 // New tests for Cli helper methods
@@ -299,7 +628,7 @@ fn test_verbose_period() {
 #[test]
 fn test_triangular_distribution_args() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["t", "0.0", "1.0", "0.5"])
+    cmd.args(["t", "0.0", "1.0", "0.5"])
         .assert()
         .success();
 }
@@ -308,7 +637,7 @@ fn test_triangular_distribution_args() {
 fn test_triangular_distribution_wait_time() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     let start = Instant::now();
-    cmd.args(&["t", "0.1", "0.5", "0.2"])
+    cmd.args(["t", "0.1", "0.5", "0.2"])
         .assert()
         .success();
     let elapsed = start.elapsed();
@@ -320,7 +649,7 @@ fn test_triangular_distribution_wait_time() {
 #[test]
 fn test_normal_distribution_args() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["n", "1s", "0.1"])
+    cmd.args(["n", "1s", "0.1"])
         .assert()
         .success();
 }
@@ -328,23 +657,101 @@ fn test_normal_distribution_args() {
 #[test]
 fn test_exponential_distribution_args() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["e", "0.5"])
+    cmd.args(["e", "0.5"])
         .assert()
         .success();
 }
 
+#[test]
+fn test_distribution_parameters_accept_durations() {
+    let dry_run = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        let assert = cmd.args(["--seed", "5", "--dry-run"]).args(args).assert().success();
+        String::from_utf8_lossy(&assert.get_output().stdout).into_owned()
+    };
+    // Each duration form samples the same as the bare number of seconds it replaces.
+    for (durations, secs) in [
+        (&["n", "10s", "2s"][..], &["n", "10s", "2"][..]),
+        (&["e", "--mean", "500ms"], &["e", "2"]),
+        (&["par", "1s", "1.5"], &["par", "1.0", "1.5"]),
+        (&["t", "1s", "10s", "5s"], &["t", "1", "10.0", "5"]),
+        (&["g", "2", "1500ms"], &["g", "2", "1.5"]),
+    ] {
+        assert_eq!(dry_run(durations), dry_run(secs), "{durations:?}");
+    }
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["e", "2", "--mean", "500ms"]).assert().failure().stderr(str::contains("cannot be used with"));
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["e"]).assert().failure().code(2).stderr(str::contains("required arguments were not provided"));
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["e", "--mean", "0s"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(str::contains("Expected one above zero, such as 500ms: 0s"));
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["t", "1x", "10s", "5s"])
+        .assert()
+        .failure()
+        .stderr(str::contains("Expected a duration such as 1500ms or a number of seconds: 1x"));
+}
+
 #[test]
 fn test_log_normal_distribution_args() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["ln", "1s", "0.1"])
+    cmd.args(["ln", "1", "0.1"])
         .assert()
         .success();
 }
 
+#[test]
+fn test_log_normal_parameterizations() {
+    for (args, params) in [
+        (&["ln", "0", "0.5"][..], "mu 0.000 and sigma 0.500: median 1s, mean 1s 133ms, 99th percentile 3s 200ms"),
+        (&["ln", "--median", "1s", "--cv", "0.5"], "mu 0.000 and sigma 0.472: median 1s, mean 1s 118ms"),
+        (&["ln", "--mean", "1s", "--cv", "0.5"], "mu -0.112 and sigma 0.472: median 894ms, mean 1s,"),
+        (&["ln", "--p50", "1s", "--p99", "10s"], "mu 0.000 and sigma 0.990: median 1s, mean 1s 632ms, 99th percentile 10s"),
+    ] {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        cmd.arg("--dry-run")
+            .args(args)
+            .assert()
+            .success()
+            .stderr(str::contains(format!("Log-normal with {params}")));
+    }
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["ln", "--median", "10ms", "--cv", "0.1", "-v"])
+        .assert()
+        .success()
+        .stderr(str::contains("[DOZR] Log-normal with mu -4.605 and sigma 0.100: median 10ms"));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["ln", "--median", "1s"]).assert().failure().stderr(str::contains("required arguments"));
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["ln", "--mean", "1s", "--p99", "3s"]).assert().failure().stderr(str::contains("cannot be used with"));
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["ln", "--median", "10s", "--p99", "1s"])
+        .assert()
+        .failure()
+        .stderr(str::contains("the 99th percentile (1s) must not be below the median (10s)"));
+
+    // MU is a logarithm, not a time, but may be negative.
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["ln", "1s", "0.5"]).assert().failure().code(2).stderr(str::contains("invalid value '1s' for '[MU]'"));
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["--dry-run", "ln", "-1", "0"])
+        .assert()
+        .success()
+        .stdout("367ms\n")
+        .stderr(str::contains("Log-normal with mu -1.000 and sigma 0.000: median 367ms"));
+}
+
 #[test]
 fn test_pareto_distribution_args() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["par", "1.0", "1.5"])
+    cmd.args(["par", "1.0", "1.5"])
         .assert()
         .success();
 }
@@ -354,7 +761,7 @@ fn test_pareto_distribution_args() {
 #[test]
 fn test_mutually_exclusive_distribution_args() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["d", "1s", "n", "1s", "0.1"])
+    cmd.args(["d", "1s", "n", "1s", "0.1"])
         .assert()
         .failure()
         .stderr(str::contains("error: unexpected argument 'n' found"));
@@ -363,7 +770,7 @@ fn test_mutually_exclusive_distribution_args() {
 #[test]
 fn test_normal_distribution_missing_param() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["n", "1s"])
+    cmd.args(["n", "1s"])
         .assert()
         .failure()
         .stderr(str::contains("required arguments were not provided"));
@@ -372,7 +779,7 @@ fn test_normal_distribution_missing_param() {
 #[test]
 fn test_normal_distribution_missing_all_params() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["n"])
+    cmd.args(["n"])
         .assert()
         .failure()
         .stderr(str::contains("required arguments were not provided"));
@@ -381,17 +788,25 @@ fn test_normal_distribution_missing_all_params() {
 #[test]
 fn test_exponential_distribution_invalid_lambda() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
-    cmd.args(&["e", "-0.5"])
+    cmd.args(["e", "-0.5"])
         .assert()
         .failure()
         .stderr(str::contains("error: unexpected argument '-0' found"));
+
+    for lambda in ["0", "inf", "NaN"] {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        cmd.args(["--dry-run", "e", lambda])
+            .assert()
+            .code(2)
+            .stderr(str::contains(format!("Invalid rate. Expected a number above zero, such as 0.5: {lambda}")));
+    }
 }
 
 #[test]
 fn test_normal_distribution_wait_time() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     let start = Instant::now();
-    cmd.args(&["n", "1s", "0.1"])
+    cmd.args(["n", "1s", "0.1"])
         .assert()
         .success();
     let elapsed = start.elapsed();
@@ -404,7 +819,7 @@ fn test_normal_distribution_wait_time() {
 fn test_exponential_distribution_wait_time() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     let start = Instant::now();
-    cmd.args(&["e", "1.0"])
+    cmd.args(["e", "1.0"])
         .assert()
         .success();
     let elapsed = start.elapsed();
@@ -417,11 +832,11 @@ fn test_exponential_distribution_wait_time() {
 fn test_log_normal_distribution_wait_time() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     let start = Instant::now();
-    cmd.args(&["ln", "1s", "0.5"])
+    cmd.args(["ln", "1", "0.5"])
         .assert()
         .success();
     let elapsed = start.elapsed();
-    // Log-Normal distribution with mean=1s, std_dev=0.5. Allow a broad range.
+    // Log-Normal distribution with mu=1, sigma=0.5 (a median of e seconds). Allow a broad range.
     assert!(elapsed > Duration::from_millis(0));
     assert!(elapsed < Duration::from_secs(10));
 }
@@ -430,7 +845,7 @@ fn test_log_normal_distribution_wait_time() {
 fn test_pareto_distribution_wait_time() {
     let mut cmd = Command::cargo_bin("dozr").unwrap();
     let start = Instant::now();
-    cmd.args(&["par", "1.0", "2.0"])
+    cmd.args(["par", "1.0", "2.0"])
         .assert()
         .success();
     let elapsed = start.elapsed();
@@ -439,3 +854,601 @@ fn test_pareto_distribution_wait_time() {
     assert!(elapsed < Duration::from_secs(10));
 }
 
+#[test]
+fn test_file_exists_already_present() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["file-exists", dir.path().to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_file_exists_timeout_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("missing");
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["f", path.to_str().unwrap(), "--timeout", "200ms", "-i", "50ms"])
+        .assert()
+        .failure()
+        .stderr(str::contains("timed out after 200ms"));
+}
+
+#[test]
+fn test_file_exists_verbose_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("marker");
+    let creator = {
+        let path = path.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(1500));
+            std::fs::write(path, b"").unwrap();
+        })
+    };
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["file-exists", path.to_str().unwrap(), "-v"])
+        .assert()
+        .success()
+        .stderr(str::contains("[DOZR] Waiting for").and(str::contains("Wait complete.")));
+    creator.join().unwrap();
+}
+
+
+#[test]
+fn test_port_wait_succeeds_when_listening() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["port", &address, "--timeout", "5s"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_port_wait_timeout_exit_code() {
+    let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["port", &address, "--timeout", "300ms", "--interval", "e 20"])
+        .assert()
+        .code(124)
+        .stderr(str::contains("timed out after 300ms"));
+}
+
+#[test]
+fn test_port_wait_invalid_address() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["port", "localhost"])
+        .assert()
+        .failure()
+        .stderr(str::contains("Expected HOST:PORT"));
+}
+
+#[test]
+fn test_port_wait_invalid_interval_spec() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["port", "localhost:1", "--interval", "bogus 1"])
+        .assert()
+        .failure()
+        .stderr(str::contains("Invalid wait specification"));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["port", "localhost:1", "--interval", "f /nonexistent/dozr-port"])
+        .assert()
+        .failure()
+        .stderr(str::contains("waits for events cannot be slept on"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pid_wait_for_unrelated_process() {
+    let mut child = std::process::Command::new("sleep").arg("1").spawn().unwrap();
+    let pid = child.id().to_string();
+    let reaper = std::thread::spawn(move || { child.wait().unwrap(); });
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["pid", &pid, "--timeout", "10s", "-v"])
+        .assert()
+        .success()
+        .stderr(str::contains(format!("[DOZR] Waiting for process {pid}")).and(str::contains("Wait complete.")));
+    assert!(start.elapsed() >= Duration::from_millis(800));
+    reaper.join().unwrap();
+}
+
+#[test]
+fn test_pid_all_and_any_are_mutually_exclusive() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["pid", "1", "--all", "--any"])
+        .assert()
+        .failure()
+        .stderr(str::contains("cannot be used with"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retry_propagates_exit_code() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["retry", "-n", "3", "-d", "10ms", "-v", "--", "sh", "-c", "exit 7"])
+        .assert()
+        .code(7)
+        .stderr(str::contains("Attempt 2 failed").and(str::contains("command failed after 3 attempts")));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retry_until_success() {
+    let dir = tempfile::tempdir().unwrap();
+    let marker = dir.path().join("attempted");
+    let script = format!("test -f {0} || {{ touch {0}; exit 1; }}", marker.display());
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["retry", "--backoff", "constant", "-d", "10ms", "--", "sh", "-c", &script])
+        .assert()
+        .success();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retry_with_distribution_wait() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["retry", "-n", "2", "--wait", "u 10ms 20ms", "--", "false"])
+        .assert()
+        .code(1);
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["retry", "--wait", "f /nonexistent/dozr-retry", "--", "false"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(str::contains("waits for events cannot be slept on"));
+}
+
+#[test]
+fn test_retry_requires_command() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["retry"])
+        .assert()
+        .failure()
+        .stderr(str::contains("required arguments were not provided"));
+}
+
+#[test]
+fn test_backoff_wait_respects_cap() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["backoff", "--attempt", "8", "--base", "10ms", "--cap", "200ms", "--strategy", "decorrelated-jitter"])
+        .assert()
+        .success();
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_backoff_invalid_strategy() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["backoff", "--strategy", "random"])
+        .assert()
+        .failure()
+        .stderr(str::contains("invalid value 'random'"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retry_with_full_jitter() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["retry", "-n", "3", "-b", "full-jitter", "-d", "10ms", "--", "false"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_cron_every_second_with_eta() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["cron", "* * * * * *", "--tz", "America/New_York", "-v"])
+        .assert()
+        .success()
+        .stderr(str::contains("[DOZR] Next run at").and(str::contains("-04:00").or(str::contains("-05:00"))));
+    assert!(start.elapsed() <= Duration::from_secs(2));
+}
+
+#[test]
+fn test_cron_invalid_expression() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["cron", "61 * * * *"])
+        .assert()
+        .failure()
+        .stderr(str::contains("Invalid cron minute field"));
+}
+
+#[test]
+fn test_cron_invalid_time_zone() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["cron", "* * * * *", "--tz", "Mars/Base"])
+        .assert()
+        .failure()
+        .stderr(str::contains("Unknown time zone"));
+}
+
+#[test]
+fn test_until_rfc3339_timestamp() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let target = chrono::Utc::now() + chrono::Duration::seconds(1);
+    let start = Instant::now();
+    cmd.args(["at", &target.to_rfc3339()]).assert().success();
+    assert!(start.elapsed() <= Duration::from_secs(2));
+}
+
+#[test]
+fn test_until_epoch_seconds() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let target = chrono::Utc::now().timestamp() + 1;
+    cmd.args(["at", &format!("@{target}")]).assert().success();
+}
+
+#[test]
+fn test_until_past_date_fails() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["at", "2020-01-01T00:00:00Z"])
+        .assert()
+        .failure()
+        .stderr(str::contains("is in the past"));
+}
+
+#[test]
+fn test_until_past_date_allowed() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["at", "2020-01-01", "--allow-past"]).assert().success();
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_until_time_in_time_zone() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    // A time of day two seconds from now on Kolkata's clock, which is offset from UTC by 5:30.
+    let target = (chrono::Utc::now() + chrono::Duration::seconds(2)).with_timezone(&chrono_tz::Asia::Kolkata);
+    let start = Instant::now();
+    cmd.args(["--tz", "Asia/Kolkata", "at", &target.format("%H:%M:%S").to_string()])
+        .assert()
+        .success();
+    assert!(start.elapsed() <= Duration::from_secs(3));
+}
+
+#[test]
+fn test_align_in_time_zone() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["align", "1s", "--tz", "Asia/Kolkata"]).assert().success();
+    assert!(start.elapsed() <= Duration::from_secs(1) + Duration::from_millis(200));
+}
+
+#[test]
+fn test_align_with_offset() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["align", "1s", "--offset", "500ms"]).assert().success();
+    assert!(start.elapsed() <= Duration::from_secs(1) + Duration::from_millis(200));
+}
+
+#[test]
+fn test_align_requires_interval_or_calendar_unit() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["align"]).assert().failure();
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["align", "1m", "--to", "day"])
+        .assert()
+        .failure()
+        .stderr(str::contains("cannot be used with"));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["align", "--to", "fortnight"])
+        .assert()
+        .failure()
+        .stderr(str::contains("invalid value 'fortnight'"));
+}
+
+#[test]
+fn test_exec_command_after_wait() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["d", "200ms", "--", "sh", "-c", "echo ran; exit 9"])
+        .assert()
+        .code(9)
+        .stdout(str::contains("ran"));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn test_exec_command_not_found() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "0s", "--", "/nonexistent/command"])
+        .assert()
+        .code(127)
+        .stderr(str::contains("failed to run /nonexistent/command"));
+}
+
+#[test]
+fn test_exec_command_skipped_with_wait() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "1s", "-p", "0", "--", "echo", "ran"])
+        .assert()
+        .success()
+        .stdout(str::is_empty());
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "1s", "-p", "0", "--run-on-skip", "--", "echo", "ran"])
+        .assert()
+        .success()
+        .stdout(str::contains("ran"));
+}
+
+#[test]
+fn test_every_runs_command_count_times() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("runs");
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let start = Instant::now();
+    cmd.args(["every", "100ms", "--count", "3", "--", "sh", "-c"])
+        .arg(format!("echo run >> {}", log.display()))
+        .assert()
+        .success();
+    assert!(start.elapsed() >= Duration::from_millis(300));
+    assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 3);
+}
+
+#[test]
+fn test_every_rejects_waits_for_events() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["every", &format!("f {}", dir.path().display()), "-n", "2", "--", "true"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(str::contains("waits for events cannot be slept on"));
+}
+
+#[test]
+fn test_every_interval_uses_global_jitter_and_tz() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    let assert = cmd
+        .args(["--seed", "1", "--print", "--print-format", "millis", "-j", "100ms"])
+        .args(["every", "0s", "-n", "3", "--", "true"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    let millis: Vec<u64> = stdout.lines().map(|line| line.parse().unwrap()).collect();
+    assert_eq!(millis.len(), 3);
+    assert!(millis.iter().any(|&m| m > 0) && millis.iter().all(|&m| m <= 100), "{millis:?}");
+
+    // The interval ends at midnight in the given zone, as the wait would on its own.
+    let end = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        let output = cmd
+            .args(["--tz", "Asia/Kolkata", "--print-format", "rfc3339"])
+            .args(args)
+            .timeout(Duration::from_secs(2))
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        chrono::DateTime::parse_from_rfc3339(stdout.lines().next().unwrap()).unwrap()
+    };
+    let every = end(&["--print", "every", "a --to day", "--", "true"]);
+    let align = end(&["--dry-run", "a", "--to", "day"]);
+    assert!((every - align).num_seconds().abs() < 1, "{every} != {align}");
+}
+
+#[test]
+fn test_every_fixed_rate_skips_overlapping_runs() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["every", "100ms", "-n", "2", "--mode", "fixed-rate", "--overlap", "skip", "-v", "--", "sleep", "0.25"])
+        .assert()
+        .success()
+        .stderr(str::contains("Skipping run"))
+        .stderr(str::contains("Finished 2 runs"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_every_prints_summary_on_interrupt() {
+    let child = std::process::Command::new(assert_cmd::cargo::cargo_bin("dozr"))
+        .args(["every", "50ms", "--", "true"])
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+    std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(130));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Interrupted after"));
+}
+
+fn interrupt_after(args: &[&str], signal: &str, delay: Duration) -> std::process::Output {
+    signal_after(args, &[(signal, delay)])
+}
+
+/// Runs dozr with `args`, sending each signal after its delay in turn.
+fn signal_after(args: &[&str], signals: &[(&str, Duration)]) -> std::process::Output {
+    let child = std::process::Command::new(assert_cmd::cargo::cargo_bin("dozr"))
+        .args(args)
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    for (signal, delay) in signals {
+        std::thread::sleep(*delay);
+        std::process::Command::new("kill")
+            .args([*signal, &child.id().to_string()])
+            .status()
+            .unwrap();
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_interrupt_reports_time_remaining() {
+    let start = std::time::Instant::now();
+    let output = interrupt_after(&["d", "30s", "-v"], "-INT", Duration::from_millis(300));
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(output.status.code(), Some(130));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Interrupted with 30s remaining"), "{stderr}");
+    assert!(!stderr.contains("Error"), "{stderr}");
+}
+
+#[test]
+fn test_terminate_is_quiet_without_verbose() {
+    let output = interrupt_after(&["d", "30s"], "-TERM", Duration::from_millis(300));
+    assert_eq!(output.status.code(), Some(130));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_interrupt_skips_the_trailing_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let marker = dir.path().join("ran");
+    let script = format!("touch {}", marker.display());
+    let output = interrupt_after(&["d", "30s", "--", "sh", "-c", &script], "-INT", Duration::from_millis(300));
+    assert_eq!(output.status.code(), Some(130));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_interrupt_ends_event_waits() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("never");
+    let start = std::time::Instant::now();
+    let output = interrupt_after(&["f", path.to_str().unwrap(), "--interval", "1h"], "-INT", Duration::from_millis(300));
+    assert_eq!(output.status.code(), Some(130));
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_usr1_reports_and_usr2_skips() {
+    let start = std::time::Instant::now();
+    let output = signal_after(
+        &["d", "30s"],
+        &[("-USR1", Duration::from_millis(300)), ("-USR2", Duration::from_millis(200))],
+    );
+    assert!(output.status.success());
+    assert!(start.elapsed() < Duration::from_secs(10));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[DOZR] Time remaining: 30s"), "{stderr}");
+}
+
+#[test]
+fn test_usr2_skips_event_waits() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("never");
+    let output = interrupt_after(&["f", path.to_str().unwrap(), "--interval", "1h"], "-USR2", Duration::from_millis(300));
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_control_file_moves_the_deadline() {
+    let dir = tempfile::tempdir().unwrap();
+    let control = dir.path().join("control");
+    std::fs::write(&control, "skip\n").unwrap();
+    let child = std::process::Command::new(assert_cmd::cargo::cargo_bin("dozr"))
+        .args(["d", "30s", "--control-file", control.to_str().unwrap()])
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+    // The "skip" already in the file is not replayed.
+    let mut file = std::fs::OpenOptions::new().append(true).open(&control).unwrap();
+    std::io::Write::write_all(&mut file, b"+1h\nstatus\nbogus\nskip\n").unwrap();
+    let start = std::time::Instant::now();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(start.elapsed() < Duration::from_secs(10));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[DOZR] Time remaining: 3630s"), "{stderr}");
+    assert!(stderr.contains("Ignoring control: unknown control 'bogus'"), "{stderr}");
+}
+
+#[test]
+fn test_control_fifo_is_reopened() {
+    let dir = tempfile::tempdir().unwrap();
+    let control = dir.path().join("control");
+    assert!(std::process::Command::new("mkfifo").arg(&control).status().unwrap().success());
+    let child = std::process::Command::new(assert_cmd::cargo::cargo_bin("dozr"))
+        .args(["d", "30s", "--control-file", control.to_str().unwrap()])
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Each write opens and closes the pipe, as `echo pause > control` would.
+    std::fs::write(&control, "pause\nstatus\n").unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+    std::fs::write(&control, "skip\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[DOZR] Paused with 30s remaining"), "{stderr}");
+}
+
+#[test]
+fn test_control_file_must_exist() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "0s", "--control-file", "/nonexistent/control"])
+        .assert()
+        .failure()
+        .stderr(str::contains("cannot read control file"));
+}
+
+#[test]
+fn test_fail_on_skip() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "0s", "-p", "0", "--fail-on-skip"])
+        .assert()
+        .code(3)
+        .stderr(str::is_empty());
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "0s", "-p", "1", "--fail-on-skip"]).assert().success();
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["d", "0s", "--fail-on-skip", "--run-on-skip", "--", "true"])
+        .assert()
+        .failure()
+        .stderr(str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_every_requires_command() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.args(["every", "1s"])
+        .assert()
+        .failure()
+        .stderr(str::contains("required arguments were not provided"));
+}
+
+#[test]
+fn test_seed_makes_probability_roll_reproducible() {
+    // With seed 2 the first roll is 0.88, so a 50% wait is always skipped.
+    for _ in 0..3 {
+        let mut cmd = Command::cargo_bin("dozr").unwrap();
+        cmd.args(["--seed", "2", "d", "0s", "-p", "0.5", "-v"])
+            .assert()
+            .success()
+            .stderr(str::contains("roll: 0.88"));
+    }
+}
+
+#[test]
+fn test_seed_from_environment() {
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.env("DOZR_SEED", "2")
+        .args(["d", "0s", "-p", "0.5", "-v"])
+        .assert()
+        .success()
+        .stderr(str::contains("roll: 0.88"));
+
+    let mut cmd = Command::cargo_bin("dozr").unwrap();
+    cmd.env("DOZR_SEED", "abc")
+        .args(["d", "0s"])
+        .assert()
+        .failure()
+        .stderr(str::contains("invalid value 'abc'"));
+}